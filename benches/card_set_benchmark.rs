@@ -3,6 +3,7 @@ use std::time::Duration;
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 
 use poker::card_set::CardSet;
+use poker::lookup_eval;
 use rand::{prelude::SliceRandom, SeedableRng, rngs::StdRng};
 
 fn canonicalize_cardsets(card_sets: &mut [CardSet]) {
@@ -18,6 +19,13 @@ fn eval_cardsets(card_sets: &mut [CardSet]) {
     }
 }
 
+fn lookup_eval_cardsets(card_sets: &[CardSet]) {
+    let tables = lookup_eval::tables();
+    for cardset in card_sets {
+        black_box(tables.evaluate(cardset));
+    }
+}
+
 fn criterion_benchmark(c: &mut Criterion) {
     let mut rng: StdRng = SeedableRng::seed_from_u64(0u64);
 
@@ -37,6 +45,11 @@ fn criterion_benchmark(c: &mut Criterion) {
         "100k random hand evaluations",
         |b| b.iter(|| eval_cardsets(black_box(&mut cardsets)))
     );
+
+    c.bench_function(
+        "100k random lookup table evaluations",
+        |b| b.iter(|| lookup_eval_cardsets(black_box(&cardsets)))
+    );
 }
 
 criterion_group!{
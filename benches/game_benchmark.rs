@@ -1,13 +1,17 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 
+use poker::card::Deck;
 use poker::round_state::RoundState;
+use poker::table_config::TableConfig;
 use rand::{SeedableRng, rngs::StdRng, Rng};
 
-fn simulate_rounds(player_count: usize, init_stack: f32, bet_size: f32, itts: i32) {
+fn simulate_rounds(player_count: usize, init_stack: f32, bet_size: f32, itts: i32, record_history: bool) {
     let mut rng: StdRng = SeedableRng::seed_from_u64(0u64);
+    let config = TableConfig { record_history, ..Default::default() };
 
     for _ in 0..itts {
-        let mut rs = RoundState::new(vec![init_stack; player_count]);
+        let mut deck = Deck::from_rng(&mut rng);
+        let mut rs = RoundState::with_config(config.clone(), vec![config.big_blinds_to_chips(init_stack); player_count], &mut deck);
 
         while !rs.is_finished() {
             let f: f32 = rng.gen();
@@ -31,14 +35,25 @@ fn criterion_benchmark(c: &mut Criterion) {
         black_box(2),
         black_box(100.0),
         black_box(10.0),
-        black_box(1_000)
+        black_box(1_000),
+        black_box(false)
     )));
 
     c.bench_function("1k rounds (8 players, ~34 actions)", |b| b.iter(|| simulate_rounds(
         black_box(8),
         black_box(100.0),
         black_box(10.0),
-        black_box(1_000)
+        black_box(1_000),
+        black_box(false)
+    )));
+
+    // Recording a HandHistory (TableConfig::record_history, on by default) costs a bit extra
+    c.bench_function("1k rounds (8 players, ~34 actions, with history)", |b| b.iter(|| simulate_rounds(
+        black_box(8),
+        black_box(100.0),
+        black_box(10.0),
+        black_box(1_000),
+        black_box(true)
     )));
 }
 
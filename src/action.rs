@@ -0,0 +1,110 @@
+use std::error::Error;
+use std::fmt;
+use crate::round_state::Chips;
+
+
+// Amounts are in chips.
+// Bet: chips put in on a street where nobody has bet yet.
+// Raise: total amount the player's bet on this street is raised to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Action {
+    Fold,
+    Check,
+    Call,
+    Bet(Chips),
+    Raise(Chips),
+    AllIn,
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Action::Fold => write!(f, "fold"),
+            Action::Check => write!(f, "check"),
+            Action::Call => write!(f, "call"),
+            Action::Bet(amount) => write!(f, "bet {}", amount),
+            Action::Raise(to) => write!(f, "raise to {}", to),
+            Action::AllIn => write!(f, "all-in"),
+        }
+    }
+}
+
+// What the player whose turn it is may do.
+// Raise amounts are "raise to" amounts: the player's total bet on this street after betting/raising.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LegalActions {
+    pub seat: usize,
+    pub can_fold: bool,
+    pub can_check: bool,
+    pub can_call: bool,
+    pub call_amount: Chips, // Chips needed to call, capped at the player's free chips
+    pub can_bet: bool, // Nobody bet on this street yet
+    pub can_raise: bool, // Facing a bet with chips left after calling, and the betting was reopened
+    pub can_all_in: bool, // False when going all-in would be a raise that isn't allowed
+    pub min_raise_to: Chips, // Capped at an all-in
+    pub max_raise_to: Chips, // All-in in no-limit, capped by the pot or the fixed bet size in pot- and fixed-limit
+}
+
+impl LegalActions {
+    pub fn is_legal(&self, action: Action) -> bool {
+        match action {
+            Action::Fold => self.can_fold,
+            Action::Check => self.can_check,
+            Action::Call => self.can_call,
+            Action::Bet(to) => self.can_bet && self.min_raise_to <= to && to <= self.max_raise_to,
+            Action::Raise(to) => self.can_raise && self.min_raise_to <= to && to <= self.max_raise_to,
+            Action::AllIn => self.can_all_in,
+        }
+    }
+}
+
+// Result of an action that was applied to a RoundState
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ActionOutcome {
+    pub seat: usize,
+    pub action: Action,
+    pub chips_added: Chips, // Chips moved from free_chips to bet_chips
+    pub stage_changed: bool,
+    pub finished: bool,
+    pub run_out: bool, // Betting closed with this action, the remaining streets are dealt without betting
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ActionError {
+    RoundFinished,
+    BettingClosed, // The board is being run out, see RoundState::advance_run_out
+    OutOfTurn { seat: usize, turn: usize },
+    CannotCheck { call_amount: Chips },
+    NothingToCall,
+    CannotBet, // There already is a bet on this street, raise instead
+    CannotRaise, // There is no bet on this street yet, bet instead
+    RaiseNotAllowed, // Not enough chips left to raise, call or go all-in instead
+    BelowMinimum { amount: Chips, min: Chips }, // Totals the street bet would be (raised) to, not the chips added
+    AboveMaximum { amount: Chips, max: Chips }, // Same totals as BelowMinimum, pot- and fixed-limit
+    NotEnoughChips { amount: Chips, available: Chips }, // Chips that would have to be added
+}
+
+impl fmt::Display for ActionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ActionError::RoundFinished => write!(f, "the round is finished"),
+            ActionError::BettingClosed => write!(f, "betting is closed, the board is being run out"),
+            ActionError::OutOfTurn { seat, turn } =>
+                write!(f, "seat {} acted out of turn, it is seat {}'s turn", seat, turn),
+            ActionError::CannotCheck { call_amount } =>
+                write!(f, "can't check when facing a bet, {} to call", call_amount),
+            ActionError::NothingToCall => write!(f, "nothing to call, check instead"),
+            ActionError::CannotBet => write!(f, "there already is a bet, raise instead"),
+            ActionError::CannotRaise => write!(f, "there is no bet to raise, bet instead"),
+            ActionError::RaiseNotAllowed => write!(f, "raising is not allowed"),
+            ActionError::BelowMinimum { amount, min } =>
+                write!(f, "{} is below the minimum of {}", amount, min),
+            ActionError::AboveMaximum { amount, max } =>
+                write!(f, "{} is above the maximum of {}", amount, max),
+            ActionError::NotEnoughChips { amount, available } =>
+                write!(f, "{} exceeds the {} chips available", amount, available),
+        }
+    }
+}
+
+impl Error for ActionError {}
@@ -1,4 +1,10 @@
+use std::error::Error;
 use std::fmt;
+use std::str::FromStr;
+
+use rand::prelude::*;
+use rand::rngs::StdRng;
+use crate::card_mask::CardMask;
 
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -13,7 +19,7 @@ impl fmt::Display for Card {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let rank = RANK_CHARS.chars().nth(self.rank() as usize);
         let suit = SUIT_CHARS.chars().nth(self.suit() as usize);
-        
+
         match (rank, suit) {
             (Some(rank), Some(suit)) => write!(f, "{}{}", rank, suit),
             _ => Err(fmt::Error)
@@ -29,17 +35,25 @@ impl From<u8> for Card {
 
 impl From<&str> for Card {
     fn from(s: &str) -> Self {
-        if s.len() != 2 {
-            panic!("Invalid input: String length must be 2 characters");
+        match s.parse() {
+            Ok(card) => card,
+            Err(e) => panic!("Invalid input: {}", e),
         }
-        
-        let rank = RANK_CHARS.find(s.chars().nth(0).unwrap());
-        let suit = SUIT_CHARS.find(s.chars().nth(1).unwrap());
+    }
+}
 
-        match (rank, suit) {
-            (Some(rank), Some(suit)) => Card::from((suit * 13 + rank) as u8),
-            _ => panic!("Invalid input: Invalid card representation"),
+impl FromStr for Card {
+    type Err = ParseCardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (card, end) = parse_card_at(s, 0)?;
+        if end != s.len() {
+            return Err(ParseCardError::UnexpectedCharacter {
+                token: s[end..].to_owned(),
+                position: end,
+            });
         }
+        Ok(card)
     }
 }
 
@@ -52,3 +66,166 @@ impl Card {
         self.0 / 13
     }
 }
+
+// Error returned when a card (or a set of cards) can't be parsed from a string.
+// 'position' is the byte offset in the input string where the offending token starts.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseCardError {
+    Empty,
+    InvalidRank { token: String, position: usize },
+    InvalidSuit { token: String, position: usize },
+    UnexpectedCharacter { token: String, position: usize },
+    DuplicateCard { card: Card, position: usize },
+    TooManyCards { max: usize, position: usize },
+}
+
+impl fmt::Display for ParseCardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseCardError::Empty => write!(f, "no card found"),
+            ParseCardError::InvalidRank { token, position } =>
+                write!(f, "invalid rank in '{}' at position {}", token, position),
+            ParseCardError::InvalidSuit { token, position } =>
+                write!(f, "invalid suit in '{}' at position {}", token, position),
+            ParseCardError::UnexpectedCharacter { token, position } =>
+                write!(f, "unexpected '{}' at position {}", token, position),
+            ParseCardError::DuplicateCard { card, position } =>
+                write!(f, "duplicate card '{}' at position {}", card, position),
+            ParseCardError::TooManyCards { max, position } =>
+                write!(f, "more than {} cards, extra card at position {}", max, position),
+        }
+    }
+}
+
+impl Error for ParseCardError {}
+
+// Parses a single card starting at byte offset 'start' of 's'.
+// Accepts upper- and lowercase ranks/suits and "10" for ten.
+// Returns the card and the byte offset right after it.
+pub(crate) fn parse_card_at(s: &str, start: usize) -> Result<(Card, usize), ParseCardError> {
+    let rest = &s[start..];
+    let rank_char = rest.chars().next().ok_or(ParseCardError::Empty)?;
+
+    let (rank, suit_offset) = if rest.starts_with("10") {
+        (RANK_CHARS.find('T'), 2)
+    } else {
+        (RANK_CHARS.find(rank_char.to_ascii_uppercase()), rank_char.len_utf8())
+    };
+    // Token reported in errors: the rank plus the character following it
+    let token = |len: usize| {
+        let end = rest[len..].chars().next().map_or(len, |c| len + c.len_utf8());
+        rest[..end].to_owned()
+    };
+    let rank = rank.ok_or_else(|| ParseCardError::InvalidRank {
+        token: token(rank_char.len_utf8()),
+        position: start,
+    })?;
+
+    let suit = rest[suit_offset..].chars().next()
+        .and_then(|c| SUIT_CHARS.find(c.to_ascii_lowercase()))
+        .ok_or_else(|| ParseCardError::InvalidSuit {
+            token: token(suit_offset),
+            position: start,
+        })?;
+
+    Ok((Card::from((suit * 13 + rank) as u8), start + suit_offset + 1))
+}
+
+// Shuffled deck of cards that deals from the top.
+// The shuffle is driven by an owned StdRng, so a deck created from a seed always deals the same cards.
+#[derive(Clone, Debug)]
+pub struct Deck {
+    cards: Vec<Card>,
+    position: usize,
+    dead: CardMask,
+    rng: StdRng,
+}
+
+impl Default for Deck {
+    fn default() -> Deck {
+        Deck::new()
+    }
+}
+
+impl Deck {
+    // Deck shuffled using entropy from the thread rng
+    pub fn new() -> Self {
+        Deck::from_rng(&mut thread_rng())
+    }
+
+    pub fn from_seed(seed: u64) -> Self {
+        Deck::with_rng(StdRng::seed_from_u64(seed))
+    }
+
+    // Seeds the deck's own rng from the given one, 'rng' is not borrowed beyond this call
+    pub fn from_rng<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        Deck::with_rng(StdRng::from_rng(rng).expect("Failed to seed deck rng"))
+    }
+
+    fn with_rng(rng: StdRng) -> Self {
+        let mut deck = Deck {
+            cards: Vec::with_capacity(52),
+            position: 0,
+            dead: CardMask::EMPTY,
+            rng,
+        };
+        deck.reset();
+        deck
+    }
+
+    // Removes the given cards from the undealt cards, they also stay out of the deck after a reset
+    pub fn exclude(&mut self, dead: CardMask) {
+        self.dead |= dead;
+        self.cards.drain(..self.position);
+        self.position = 0;
+        self.cards.retain(|&card| !dead.contains(card));
+    }
+
+    // Collects all non-dead cards and reshuffles them
+    pub fn reset(&mut self) {
+        self.cards.clear();
+        self.cards.extend((!self.dead).iter());
+        self.cards.shuffle(&mut self.rng);
+        self.position = 0;
+    }
+
+    // Panics if fewer than n cards are left
+    pub fn deal(&mut self, n: usize) -> Vec<Card> {
+        let cards = self.peek(n).to_vec();
+        self.position += n;
+        cards
+    }
+
+    pub fn deal_one(&mut self) -> Option<Card> {
+        let card = self.cards.get(self.position).copied();
+        if card.is_some() {
+            self.position += 1;
+        }
+        card
+    }
+
+    // The next n cards that would be dealt, panics if fewer than n cards are left
+    pub fn peek(&self, n: usize) -> &[Card] {
+        if n > self.remaining() {
+            panic!("Can't deal {} cards, only {} left in the deck", n, self.remaining());
+        }
+        &self.cards[self.position..self.position+n]
+    }
+
+    // Discards the top card, returns it if there was one
+    pub fn burn(&mut self) -> Option<Card> {
+        self.deal_one()
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.cards.len() - self.position
+    }
+
+    pub fn remaining_cards(&self) -> CardMask {
+        self.cards[self.position..].iter().copied().collect()
+    }
+
+    pub fn dead_cards(&self) -> CardMask {
+        self.dead
+    }
+}
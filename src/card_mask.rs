@@ -0,0 +1,234 @@
+use std::fmt;
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Not, Sub, SubAssign};
+use std::str::FromStr;
+use crate::card::{Card, ParseCardError};
+use crate::card_set::{parse_cards, CardSet};
+
+
+// Set of cards stored as a bitmask, bit 'card.0' is set when the card is in the set.
+// Unlike CardSet it can hold any amount of cards (e.g. a deck or a list of dead cards).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct CardMask(
+    pub u64
+);
+
+impl CardMask {
+    pub const EMPTY: CardMask = CardMask(0);
+    pub const FULL: CardMask = CardMask((1 << 52) - 1);
+
+    pub fn new() -> Self {
+        CardMask::EMPTY
+    }
+
+    pub fn contains(&self, card: Card) -> bool {
+        self.0 & (1 << card.0) != 0
+    }
+
+    // Returns true if the card was not yet in the set
+    pub fn insert(&mut self, card: Card) -> bool {
+        let absent = !self.contains(card);
+        self.0 |= 1 << card.0;
+        absent
+    }
+
+    // Returns true if the card was in the set
+    pub fn remove(&mut self, card: Card) -> bool {
+        let present = self.contains(card);
+        self.0 &= !(1 << card.0);
+        present
+    }
+
+    pub fn union(&self, other: CardMask) -> CardMask {
+        CardMask(self.0 | other.0)
+    }
+
+    pub fn intersection(&self, other: CardMask) -> CardMask {
+        CardMask(self.0 & other.0)
+    }
+
+    pub fn difference(&self, other: CardMask) -> CardMask {
+        CardMask(self.0 & !other.0)
+    }
+
+    pub fn is_disjoint(&self, other: CardMask) -> bool {
+        self.0 & other.0 == 0
+    }
+
+    pub fn is_subset(&self, other: CardMask) -> bool {
+        self.0 & !other.0 == 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    // Iterates the cards from lowest to highest index
+    pub fn iter(&self) -> CardMaskIter {
+        CardMaskIter(self.0)
+    }
+
+    // 13 bit mask of the ranks present in the given suit
+    pub fn suit_ranks(&self, suit: u8) -> u16 {
+        ((self.0 >> (13 * suit as u64)) & 0x1FFF) as u16
+    }
+}
+
+pub struct CardMaskIter(u64);
+
+impl Iterator for CardMaskIter {
+    type Item = Card;
+
+    fn next(&mut self) -> Option<Card> {
+        if self.0 == 0 {
+            return None;
+        }
+        let card = Card(self.0.trailing_zeros() as u8);
+        self.0 &= self.0 - 1;
+        Some(card)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.0.count_ones() as usize;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for CardMaskIter {}
+
+impl IntoIterator for CardMask {
+    type Item = Card;
+    type IntoIter = CardMaskIter;
+
+    fn into_iter(self) -> CardMaskIter {
+        self.iter()
+    }
+}
+
+impl IntoIterator for &CardMask {
+    type Item = Card;
+    type IntoIter = CardMaskIter;
+
+    fn into_iter(self) -> CardMaskIter {
+        self.iter()
+    }
+}
+
+impl fmt::Display for CardMask {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cards_as_strings: Vec<String> = self.iter().map(|card| format!("{}", card)).collect();
+        write!(f, "{}", cards_as_strings.join(" "))
+    }
+}
+
+impl FromStr for CardMask {
+    type Err = ParseCardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(parse_cards(s, 52)?.into_iter().collect())
+    }
+}
+
+impl From<&str> for CardMask {
+    fn from(s: &str) -> Self {
+        match s.parse() {
+            Ok(card_mask) => card_mask,
+            Err(e) => panic!("Invalid input: {}", e),
+        }
+    }
+}
+
+impl From<Card> for CardMask {
+    fn from(card: Card) -> Self {
+        CardMask(1 << card.0)
+    }
+}
+
+impl From<&[Card]> for CardMask {
+    fn from(cards: &[Card]) -> Self {
+        cards.iter().copied().collect()
+    }
+}
+
+impl From<&CardSet> for CardMask {
+    fn from(card_set: &CardSet) -> Self {
+        card_set.iter().copied().collect()
+    }
+}
+
+impl From<CardSet> for CardMask {
+    fn from(card_set: CardSet) -> Self {
+        (&card_set).into()
+    }
+}
+
+impl From<CardMask> for CardSet {
+    // Panics if the mask holds more than 7 cards, like CardSet::new
+    fn from(card_mask: CardMask) -> Self {
+        card_mask.iter().collect()
+    }
+}
+
+impl FromIterator<Card> for CardMask {
+    fn from_iter<I: IntoIterator<Item = Card>>(iter: I) -> Self {
+        let mut card_mask = CardMask::EMPTY;
+        for card in iter {
+            card_mask.insert(card);
+        }
+        card_mask
+    }
+}
+
+impl BitOr for CardMask {
+    type Output = CardMask;
+
+    fn bitor(self, rhs: CardMask) -> CardMask {
+        self.union(rhs)
+    }
+}
+
+impl BitOrAssign for CardMask {
+    fn bitor_assign(&mut self, rhs: CardMask) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl BitAnd for CardMask {
+    type Output = CardMask;
+
+    fn bitand(self, rhs: CardMask) -> CardMask {
+        self.intersection(rhs)
+    }
+}
+
+impl BitAndAssign for CardMask {
+    fn bitand_assign(&mut self, rhs: CardMask) {
+        self.0 &= rhs.0;
+    }
+}
+
+impl Sub for CardMask {
+    type Output = CardMask;
+
+    fn sub(self, rhs: CardMask) -> CardMask {
+        self.difference(rhs)
+    }
+}
+
+impl SubAssign for CardMask {
+    fn sub_assign(&mut self, rhs: CardMask) {
+        self.0 &= !rhs.0;
+    }
+}
+
+impl Not for CardMask {
+    type Output = CardMask;
+
+    // Complement within the 52 card deck
+    fn not(self) -> CardMask {
+        CardMask::FULL.difference(self)
+    }
+}
@@ -1,6 +1,8 @@
 use std::fmt::{self};
 use std::slice::{Iter, IterMut};
-use crate::card::Card;
+use std::str::FromStr;
+use crate::card::{parse_card_at, Card, ParseCardError};
+use crate::hand_rank::{HandCategory, HandRank};
 
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -9,6 +11,14 @@ pub struct CardSet {
     size: usize
 }
 
+// The 5 cards that make up a hand, see CardSet::best_five
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BestFive {
+    pub cards: CardSet, // Original suits, the made hand (e.g. the pair) first and then the kickers, high to low
+    pub hand_rank: HandRank,
+    pub kickers: CardSet, // Cards outside the made hand, empty for straights, flushes and full houses
+}
+
 impl fmt::Display for CardSet {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let cards_as_strings: Vec<String> = self.iter().map(|card| format!("{}", card)).collect();
@@ -31,8 +41,7 @@ impl From<Vec<u8>> for CardSet {
 
 impl From<&[Card]> for CardSet {
     fn from(c: &[Card]) -> Self {
-        let vec = c.iter().map(|&x| x.into()).collect::<Vec<Card>>();
-        vec.into()
+        c.to_vec().into()
     }
 }
 
@@ -44,15 +53,47 @@ impl From<&[u8]> for CardSet {
 }
 
 impl From<&str> for CardSet {
-	fn from(s: &str) -> Self {
-        // Format example:
-        // "4h*2c*3c*As*9s*Qs" where '*' is any character (wildcard)
-		let mut cards = Vec::new();
-		for i in 0..(s.chars().count()+1)/3 {
-            cards.push(Card::from(&s[i*3..(i*3+2)]));
-		}
-        cards.into()
-	}
+    fn from(s: &str) -> Self {
+        match s.parse() {
+            Ok(card_set) => card_set,
+            Err(e) => panic!("Invalid input: {}", e),
+        }
+    }
+}
+
+impl FromStr for CardSet {
+    type Err = ParseCardError;
+
+    // Format examples: "4h 2c 3c", "4h,2c,3c", "4h2c3c", "10h Jh"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let cards = parse_cards(s, 7)?;
+        Ok(CardSet::new(&cards))
+    }
+}
+
+// Parses cards separated by spaces, commas or nothing at all.
+// Duplicate cards and more than 'max' cards are rejected.
+pub(crate) fn parse_cards(s: &str, max: usize) -> Result<Vec<Card>, ParseCardError> {
+    let mut cards = Vec::new();
+    let mut seen = 0u64;
+    let mut position = 0;
+    while let Some(c) = s[position..].chars().next() {
+        if c.is_whitespace() || c == ',' {
+            position += c.len_utf8();
+            continue;
+        }
+        let (card, end) = parse_card_at(s, position)?;
+        if seen & (1 << card.0) != 0 {
+            return Err(ParseCardError::DuplicateCard { card, position });
+        }
+        if cards.len() == max {
+            return Err(ParseCardError::TooManyCards { max, position });
+        }
+        seen |= 1 << card.0;
+        cards.push(card);
+        position = end;
+    }
+    Ok(cards)
 }
 
 impl FromIterator<Card> for CardSet {
@@ -77,21 +118,23 @@ impl CardSet {
 
     pub fn set_cards_partial(&mut self, cards: &[Card], offset: usize) {
         self.size = self.size.max(cards.len() + offset);
-        for i in 0..cards.len() {
-            self.cards[offset+i] = cards[i];
-        }
+        self.cards[offset..offset+cards.len()].copy_from_slice(cards);
     }
 
     pub fn len(&self) -> usize {
         self.size
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
     pub fn iter(&self) -> Iter<'_, Card> {
-        self.cards[..self.size as usize].iter()
+        self.cards[..self.size].iter()
     }
 
     pub fn iter_mut(&mut self) -> IterMut<'_, Card> {
-        self.cards[..self.size as usize].iter_mut()
+        self.cards[..self.size].iter_mut()
     }
 
     // Uses only 6*7 = 42 bits of the 64
@@ -108,24 +151,20 @@ impl CardSet {
         self
     }
 
-    // ~ 0.5 us
+    // Without allocations
     pub fn canonicalize(&mut self) {
         const PRIMES: [u64; 13] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41];
-        
-        let ranks: Vec<u8> = self.iter().map(|&c| c.rank()).collect();
-        let suits: Vec<u8> = self.iter().map(|&c| c.suit()).collect();
 
         // Determine suit mapping
         let mut suit_count: [usize; 4] = [0; 4];
-        for &suit in suits.iter() {
-            suit_count[suit as usize] += 1;
-        }
         let mut equal_suit_count_ranking: [u64; 4] = [1; 4];
-        for (&rank, &suit) in ranks.iter().zip(suits.iter()) {
-            equal_suit_count_ranking[suit as usize] *= PRIMES[rank as usize];
+        for &card in self.iter() {
+            suit_count[card.suit() as usize] += 1;
+            equal_suit_count_ranking[card.suit() as usize] *= PRIMES[card.rank() as usize];
         }
-        let mut inv_suit_mapping: Vec<usize> = (0..4).collect();
-        inv_suit_mapping.sort_by_key(|&i| (suit_count[i], equal_suit_count_ranking[i]));
+        // Ties keep the original suit order
+        let mut inv_suit_mapping: [usize; 4] = [0, 1, 2, 3];
+        inv_suit_mapping.sort_unstable_by_key(|&i| (suit_count[i], equal_suit_count_ranking[i], i));
 
         let mut suit_mapping: [u8; 4] = [0; 4];
         for (i, &inv_suit_map) in inv_suit_mapping.iter().enumerate() {
@@ -133,17 +172,74 @@ impl CardSet {
         }
 
         // Apply suit mapping and sort
-        let mut out = ranks.iter().zip(suits.iter())
-            .map(|(&rank, &suit)| Card(13 * suit_mapping[suit as usize] + rank))
-            .collect::<Vec<Card>>();
-        out.sort_by_key(|&x| 255u8 - x.0);
+        for card in self.iter_mut() {
+            *card = Card(13 * suit_mapping[card.suit() as usize] + card.rank());
+        }
+        self.cards[..self.size].sort_unstable_by_key(|&x| 255u8 - x.0);
+    }
 
-        self.set_cards_partial(&out, 0);
+    pub fn is_canonical(&self) -> bool {
+        *self == self.clone().as_canonical()
     }
 
-    // ~ 0.25 us
+    // Evaluates 5 to 7 cards in any order and suits, see evaluate()
+    pub fn evaluate_any(&self) -> i32 {
+        if !(5..=7).contains(&self.len()) {
+            panic!("Can only evaluate 5 to 7 cards, got {}", self.len());
+        }
+        self.clone().as_canonical().evaluate()
+    }
+
+    // Best 5 card subset of 5 to 7 cards in their original suits, doesn't need canonical form.
+    // Takes up to 21 evaluations.
+    pub fn best_five(&self) -> BestFive {
+        let mut best: Option<(Vec<Card>, HandRank)> = None;
+        for skip_mask in 0u32..(1 << self.len()) {
+            if self.len() - skip_mask.count_ones() as usize != 5 {
+                continue;
+            }
+            let subset: Vec<Card> = self.iter().enumerate()
+                .filter(|&(i, _)| skip_mask & (1 << i) == 0)
+                .map(|(_, &card)| card)
+                .collect();
+            let hand_rank = HandRank(CardSet::from(&subset[..]).evaluate_any());
+            if best.as_ref().is_none_or(|(_, best_rank)| hand_rank > *best_rank) {
+                best = Some((subset, hand_rank));
+            }
+        }
+        let (mut cards, hand_rank) = best.expect("Need atleast 5 cards");
+
+        let mut rank_counts = [0u8; 13];
+        for card in cards.iter() {
+            rank_counts[card.rank() as usize] += 1;
+        }
+        let category = hand_rank.category();
+        let is_wheel = matches!(category, HandCategory::Straight | HandCategory::StraightFlush) && hand_rank.ranks()[0] == 3;
+        // Bigger groups first, the ace of a wheel (5 4 3 2 A) last
+        cards.sort_by_key(|card| {
+            let rank = if is_wheel && card.rank() == 12 { 0 } else { card.rank() + 1 };
+            std::cmp::Reverse((rank_counts[card.rank() as usize], rank, card.suit()))
+        });
+        let kickers: Vec<Card> = match category {
+            HandCategory::HighCard => cards[1..].to_vec(),
+            HandCategory::Pair | HandCategory::TwoPair | HandCategory::ThreeOfAKind | HandCategory::FourOfAKind => {
+                cards.iter().copied().filter(|card| rank_counts[card.rank() as usize] == 1).collect()
+            }
+            _ => vec![],
+        };
+        BestFive { cards: CardSet::from(cards), hand_rank, kickers: CardSet::from(kickers) }
+    }
+
+    // Typed evaluate(), with the category and the ranks that decide the hand
     // Expects the card vector to be canonicalized
-    // Expects the card vector to be of length 7
+    pub fn hand_rank(&self) -> HandRank {
+        HandRank(self.evaluate())
+    }
+
+    // ~ 0.25 us
+    // Expects the card vector to be canonicalized (checked in debug builds), use evaluate_any() otherwise.
+    // Expects 5 to 7 cards, only the best 5 count so ranks of different sizes compare correctly
+    #[allow(clippy::erasing_op, clippy::identity_op)]
     pub fn evaluate(&self) -> i32 {
         // --- Outline algorithm ---
         // if Flush:
@@ -169,8 +265,8 @@ impl CardSet {
         // else:
         //   return HIGH_CARD
 
-        // TODO: Check if len 7
-        // TODO: check if canonicalized
+        debug_assert!((5..=7).contains(&self.len()), "Can only evaluate 5 to 7 cards, got {}", self.len());
+        debug_assert!(self.is_canonical(), "Evaluating non canonical cards '{}', use evaluate_any()", self);
 
         const HIGH_CARD_START : i32      = 0 * (1 << 20) + 1;
         const PAIR_START : i32           = 1 * (1 << 20);
@@ -182,8 +278,13 @@ impl CardSet {
         const FOUR_OF_KIND_START : i32   = 7 * (1 << 20);
         const STRAIGHT_FLUSH_START : i32 = 8 * (1 << 20);
 
-        let mut ranks: Vec<_> = self.iter().map(|&c| c.rank()).collect();
+        let mut rank_buffer = [0u8; 7];
+        for (rank, card) in rank_buffer.iter_mut().zip(self.iter()) {
+            *rank = card.rank();
+        }
+        let ranks = &mut rank_buffer[..self.size];
 
+        // Canonical form puts the most common suit last (3) and sorts its cards first
         let is_flush = self.cards[4].suit() == 3;
         if is_flush {
             let mut current_straight_count = 1;
@@ -216,7 +317,7 @@ impl CardSet {
         }
         // CardSet is not a flush, so not 'Flush', 'Straight Flush', or 'Royal Flush'
         // Suit doesn't matter beyond this point => sort ranks as this is easier to work with
-        ranks.sort_by(|a, b| b.cmp(&a));
+        ranks.sort_unstable_by(|a, b| b.cmp(a));
 
         // n-of-a-kind
         let mut three_kind = 255u8;
@@ -235,14 +336,13 @@ impl CardSet {
             if kind_count == 4 {
                 // Four of a kind
                 let four_kind = ranks[i-1];
-                let kicker_value: i32;
-                if ranks[0] == four_kind {
+                let kicker_value = if ranks[0] == four_kind {
                     // kicker is smaller than the rank of the four of a kind
-                    kicker_value = ranks[4] as i32;
+                    ranks[4] as i32
                 } else {
                     // kicker is larger than the rank of the four of a kind
-                    kicker_value = ranks[0] as i32;
-                }
+                    ranks[0] as i32
+                };
                 return FOUR_OF_KIND_START + 13 * (four_kind as i32) + kicker_value;
             } else if three_kind == 255u8 && kind_count == 3 {
                 three_kind = ranks[i-1];
@@ -298,7 +398,7 @@ impl CardSet {
                 }
                 i += 1;
             }
-            return THREE_OF_KIND_START + 156 * (three_kind as i32) + 12 * kicker_0 + kicker_1;
+            THREE_OF_KIND_START + 156 * (three_kind as i32) + 12 * kicker_0 + kicker_1
         } else if two_kind_h != 255u8 {
             if two_kind_l != 255u8 {
                 // Two pair
@@ -312,7 +412,7 @@ impl CardSet {
                     kicker = ranks[i] as i32;
                     break;
                 }
-                return TWO_PAIR_START + 156 * ((two_kind_h as i32) - 1) + 13 * (two_kind_l as i32) + kicker;
+                TWO_PAIR_START + 156 * ((two_kind_h as i32) - 1) + 13 * (two_kind_l as i32) + kicker
             } else {
                 // Pair
                 let mut kicker_0: i32 = -1;
@@ -334,16 +434,16 @@ impl CardSet {
                     }
                     i += 1;
                 }
-                return PAIR_START + 1716 * (two_kind_h as i32) + 132 * kicker_0 + 11 * kicker_1 + kicker_2;
+                PAIR_START + 1716 * (two_kind_h as i32) + 132 * kicker_0 + 11 * kicker_1 + kicker_2
             }
         } else {
             // High card
-            return HIGH_CARD_START + 
+            HIGH_CARD_START + 
                 (ranks[0] as i32) * 11880 +
                 (ranks[1] as i32) * 990 +
                 (ranks[2] as i32) * 90 +
                 (ranks[3] as i32) * 9 +
-                (ranks[4] as i32);
+                (ranks[4] as i32)
         }
     }
 }
\ No newline at end of file
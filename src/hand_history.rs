@@ -0,0 +1,59 @@
+use crate::action::Action;
+use crate::card_set::CardSet;
+use crate::round_state::{Chips, RoundStage};
+
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ForcedBet {
+    Ante,
+    SmallBlind,
+    BigBlind,
+    BigBlindAnte,
+    Straddle,
+}
+
+impl ForcedBet {
+    // Live bets count towards the player's bet on the street, antes are dead money
+    pub fn is_live(&self) -> bool {
+        matches!(self, ForcedBet::SmallBlind | ForcedBet::BigBlind | ForcedBet::Straddle)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum HandEvent {
+    Post { seat: usize, forced_bet: ForcedBet, amount: Chips },
+    HoleCards { seat: usize, cards: CardSet },
+    Action {
+        seat: usize,
+        stage: RoundStage,
+        action: Action,
+        chips_added: Chips,
+        pot: Chips, // All chips bet after the action, antes and blinds included
+    },
+    Street { stage: RoundStage, cards: CardSet }, // Community cards revealed on the street
+}
+
+// Everything that happened in a round in order: forced bets, dealt cards and actions.
+// Recorded by RoundState unless TableConfig::record_history is turned off.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HandHistory {
+    pub events: Vec<HandEvent>,
+}
+
+impl HandHistory {
+    pub fn push(&mut self, event: HandEvent) {
+        self.events.push(event);
+    }
+
+    pub fn actions(&self) -> impl Iterator<Item = &HandEvent> {
+        self.events.iter().filter(|event| matches!(event, HandEvent::Action { .. }))
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+}
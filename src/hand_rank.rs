@@ -0,0 +1,129 @@
+use std::fmt;
+
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum HandCategory {
+    HighCard,
+    Pair,
+    TwoPair,
+    ThreeOfAKind,
+    Straight,
+    Flush,
+    FullHouse,
+    FourOfAKind,
+    StraightFlush,
+}
+
+impl HandCategory {
+    pub fn name(&self) -> &'static str {
+        match self {
+            HandCategory::HighCard => "High Card",
+            HandCategory::Pair => "Pair",
+            HandCategory::TwoPair => "Two Pair",
+            HandCategory::ThreeOfAKind => "Three of a Kind",
+            HandCategory::Straight => "Straight",
+            HandCategory::Flush => "Flush",
+            HandCategory::FullHouse => "Full House",
+            HandCategory::FourOfAKind => "Four of a Kind",
+            HandCategory::StraightFlush => "Straight Flush",
+        }
+    }
+}
+
+// Evaluation of a hand (see CardSet::evaluate), a higher rank wins.
+// The category is in the bits from 20 on, the rest encodes the ranks that matter within the category.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HandRank(
+    pub i32
+);
+
+const CATEGORIES: [HandCategory; 9] = [
+    HandCategory::HighCard,
+    HandCategory::Pair,
+    HandCategory::TwoPair,
+    HandCategory::ThreeOfAKind,
+    HandCategory::Straight,
+    HandCategory::Flush,
+    HandCategory::FullHouse,
+    HandCategory::FourOfAKind,
+    HandCategory::StraightFlush,
+];
+
+const RANK_NAMES: [&str; 13] = ["Two", "Three", "Four", "Five", "Six", "Seven", "Eight", "Nine", "Ten", "Jack", "Queen", "King", "Ace"];
+
+// e.g. "Ace", "Sixes"
+pub fn rank_name(rank: u8, plural: bool) -> String {
+    let name = RANK_NAMES[rank as usize];
+    match (plural, rank) {
+        (false, _) => name.to_owned(),
+        (true, 4) => "Sixes".to_owned(),
+        (true, _) => format!("{}s", name),
+    }
+}
+
+impl From<i32> for HandRank {
+    fn from(score: i32) -> Self {
+        HandRank(score)
+    }
+}
+
+impl HandRank {
+    pub fn category(&self) -> HandCategory {
+        CATEGORIES[(self.0 >> 20) as usize]
+    }
+
+    // The ranks that decide the hand within its category (2 = 0, ..., Ace = 12), most important first:
+    // the pair, trips or quads before the kickers, the highest card of a straight.
+    pub fn ranks(&self) -> Vec<u8> {
+        let value = self.0 & ((1 << 20) - 1);
+        let mixed_radix = |value: i32, radices: &[i32]| -> Vec<u8> {
+            let mut rest = value;
+            radices.iter().map(|&radix| {
+                let rank = rest / radix;
+                rest %= radix;
+                rank as u8
+            }).collect()
+        };
+        match self.category() {
+            // The high card category starts at 1
+            HandCategory::HighCard => mixed_radix(value - 1, &[11880, 990, 90, 9, 1]),
+            HandCategory::Flush => mixed_radix(value, &[11880, 990, 90, 9, 1]),
+            HandCategory::Pair => mixed_radix(value, &[1716, 132, 11, 1]),
+            HandCategory::TwoPair => {
+                let ranks = mixed_radix(value, &[156, 13, 1]);
+                vec![ranks[0] + 1, ranks[1], ranks[2]]
+            }
+            HandCategory::ThreeOfAKind => mixed_radix(value, &[156, 12, 1]),
+            HandCategory::FullHouse | HandCategory::FourOfAKind => mixed_radix(value, &[13, 1]),
+            // 0 is the wheel (five high), otherwise 1 + the lowest rank
+            HandCategory::Straight | HandCategory::StraightFlush => vec![if value == 0 { 3 } else { value as u8 + 3 }],
+        }
+    }
+
+    // e.g. "Two Pair, Kings and Fives, Ace kicker"
+    pub fn describe(&self) -> String {
+        let ranks = self.ranks();
+        let name = |rank: u8| rank_name(rank, false);
+        let names = |ranks: &[u8]| ranks.iter().map(|&rank| name(rank)).collect::<Vec<_>>().join("-");
+        let kickers = |ranks: &[u8]| format!("{} kicker{}", names(ranks), if ranks.len() > 1 { "s" } else { "" });
+        let category = self.category().name();
+        match self.category() {
+            HandCategory::HighCard | HandCategory::Flush => format!("{}, {}", category, names(&ranks)),
+            HandCategory::Pair | HandCategory::ThreeOfAKind | HandCategory::FourOfAKind => {
+                format!("{}, {}, {}", category, rank_name(ranks[0], true), kickers(&ranks[1..]))
+            }
+            HandCategory::TwoPair => {
+                format!("{}, {} and {}, {}", category, rank_name(ranks[0], true), rank_name(ranks[1], true), kickers(&ranks[2..]))
+            }
+            HandCategory::FullHouse => format!("{}, {} full of {}", category, rank_name(ranks[0], true), rank_name(ranks[1], true)),
+            HandCategory::StraightFlush if ranks[0] == 12 => "Royal Flush".to_owned(),
+            HandCategory::Straight | HandCategory::StraightFlush => format!("{}, {} high", category, name(ranks[0])),
+        }
+    }
+}
+
+impl fmt::Display for HandRank {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.describe())
+    }
+}
@@ -1,3 +1,14 @@
+pub mod action;
 pub mod card;
+pub mod card_mask;
 pub mod card_set;
+pub mod hand_history;
+pub mod hand_rank;
+pub mod lookup_eval;
+pub mod player_view;
+pub mod pokerstars;
+pub mod pots;
 pub mod round_state;
+pub mod showdown;
+pub mod table;
+pub mod table_config;
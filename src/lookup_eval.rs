@@ -0,0 +1,143 @@
+use std::sync::OnceLock;
+use crate::card::Card;
+use crate::card_mask::CardMask;
+use crate::card_set::CardSet;
+
+
+// --- LOOKUP TABLE EVALUATION ---
+// Gives the same scores as CardSet::evaluate for 5 to 7 cards, in any order and suits, without canonicalizing.
+// The tables are generated with CardSet::evaluate on first use:
+// - Flushes: indexed by the 13 bit rank mask of the flush suit. With 7 cards or less a flush rules out
+//   quads and full houses, so the other suits don't matter.
+// - Everything else: indexed by a perfect hash of the rank counts, one table per amount of cards.
+//   The suits don't matter when there is no flush.
+//
+// --- RANK COUNT HASH ---
+// Every card adds a precomputed key, so the rank counts are known without sorting or counting per rank:
+// base 5 digits of the counts of the low ranks (2 to 8), the high ranks (9 to A), and a 4 bit counter per suit.
+// Rank counts are ordered by their high ranks first: the position of the high ranks (base_offsets) plus the
+// position of the low ranks among those with the same amount of cards (low_offsets).
+
+const LOW_RANKS: usize = 7;
+const HIGH_RANKS: usize = 6;
+const LOW_CODES: usize = 78125; // 5^7
+const HIGH_CODES: usize = 15625; // 5^6
+const HIGH_SHIFT: u64 = 17; // Low codes fit in 17 bits
+const SUIT_SHIFT: u64 = 32;
+
+pub struct LookupTables {
+    card_keys: [u64; 52],
+    flush: Vec<i32>, // By rank mask, 0 for less than 5 ranks
+    low_offsets: Vec<u16>, // By low code
+    base_offsets: [Vec<u32>; 3], // By high code, for 5, 6 and 7 cards
+    scores: [Vec<i32>; 3], // By rank count position, for 5, 6 and 7 cards
+}
+
+// Base 5 digits of the rank counts, ranks with 0 cards included
+fn count_codes(code: usize, digits: usize) -> Vec<usize> {
+    (0..digits).map(|digit| code / 5usize.pow(digit as u32) % 5).collect()
+}
+
+impl LookupTables {
+    // Takes a few milliseconds, lookup_eval::tables() generates them once
+    pub fn generate() -> Self {
+        let card_keys = std::array::from_fn(|card| {
+            let card = Card(card as u8);
+            let (rank, suit) = (card.rank() as u64, card.suit() as u64);
+            let rank_key = if rank < LOW_RANKS as u64 {
+                5u64.pow(rank as u32)
+            } else {
+                5u64.pow((rank - LOW_RANKS as u64) as u32) << HIGH_SHIFT
+            };
+            rank_key | 1 << (SUIT_SHIFT + 4 * suit)
+        });
+
+        let mut flush = vec![0; 1 << 13];
+        for (rank_mask, score) in flush.iter_mut().enumerate() {
+            if (5..=7).contains(&rank_mask.count_ones()) {
+                let cards: Vec<Card> = (0..13).filter(|rank| rank_mask & (1 << rank) != 0).map(Card).collect();
+                *score = CardSet::from(cards).evaluate_any();
+            }
+        }
+
+        // Low ranks: position among the low rank counts with the same amount of cards
+        let mut low_offsets = vec![0u16; LOW_CODES];
+        let mut low_codes_by_count: Vec<Vec<usize>> = vec![vec![]; 8];
+        for (code, offset) in low_offsets.iter_mut().enumerate() {
+            let counts = count_codes(code, LOW_RANKS);
+            let card_count: usize = counts.iter().sum();
+            if card_count <= 7 && counts.iter().all(|&count| count <= 4) {
+                *offset = low_codes_by_count[card_count].len() as u16;
+                low_codes_by_count[card_count].push(code);
+            }
+        }
+
+        let mut base_offsets = [vec![0u32; HIGH_CODES], vec![0u32; HIGH_CODES], vec![0u32; HIGH_CODES]];
+        let mut scores = [vec![], vec![], vec![]];
+        for ((base_offsets, table), card_count) in base_offsets.iter_mut().zip(scores.iter_mut()).zip(5..=7) {
+            for (high_code, base_offset) in base_offsets.iter_mut().enumerate() {
+                let high_counts = count_codes(high_code, HIGH_RANKS);
+                let high_card_count: usize = high_counts.iter().sum();
+                if high_card_count > card_count || high_counts.iter().any(|&count| count > 4) {
+                    continue;
+                }
+                *base_offset = table.len() as u32;
+                for &low_code in low_codes_by_count[card_count - high_card_count].iter() {
+                    // Suits go round so no suit gets more than 2 cards, a flush is not possible
+                    let counts = count_codes(low_code, LOW_RANKS).into_iter().chain(high_counts.iter().copied());
+                    let cards: Vec<Card> = counts.enumerate()
+                        .flat_map(|(rank, count)| std::iter::repeat_n(rank as u8, count))
+                        .enumerate()
+                        .map(|(i, rank)| Card(13 * (i % 4) as u8 + rank))
+                        .collect();
+                    table.push(CardSet::from(cards).evaluate_any());
+                }
+            }
+        }
+        LookupTables { card_keys, flush, low_offsets, base_offsets, scores }
+    }
+
+    // 'suit_ranks' gives the rank mask of a suit, only needed for flushes
+    fn lookup(&self, key: u64, card_count: usize, suit_ranks: impl Fn(u8) -> u16) -> i32 {
+        if !(5..=7).contains(&card_count) {
+            panic!("Can only evaluate 5 to 7 cards, got {}", card_count);
+        }
+        // A suit counter reaches 8 after adding 3 when the suit has 5 cards or more
+        let flush_bits = ((key >> SUIT_SHIFT) + 0x3333) & 0x8888;
+        if flush_bits != 0 {
+            return self.flush[suit_ranks(flush_bits.trailing_zeros() as u8 / 4) as usize];
+        }
+        let low_code = (key & ((1 << HIGH_SHIFT) - 1)) as usize;
+        let high_code = ((key >> HIGH_SHIFT) & ((1 << (SUIT_SHIFT - HIGH_SHIFT)) - 1)) as usize;
+        let position = self.base_offsets[card_count - 5][high_code] as usize + self.low_offsets[low_code] as usize;
+        self.scores[card_count - 5][position]
+    }
+
+    // 5 to 7 cards in any order and suits
+    pub fn evaluate(&self, cards: &CardSet) -> i32 {
+        let key = cards.iter().map(|card| self.card_keys[card.0 as usize]).sum();
+        self.lookup(key, cards.len(), |suit| {
+            cards.iter().filter(|card| card.suit() == suit).fold(0, |mask, card| mask | 1 << card.rank())
+        })
+    }
+
+    pub fn evaluate_mask(&self, mask: CardMask) -> i32 {
+        let key = mask.iter().map(|card| self.card_keys[card.0 as usize]).sum();
+        self.lookup(key, mask.len(), |suit| mask.suit_ranks(suit))
+    }
+}
+
+// Generated on first use
+pub fn tables() -> &'static LookupTables {
+    static TABLES: OnceLock<LookupTables> = OnceLock::new();
+    TABLES.get_or_init(LookupTables::generate)
+}
+
+// Same score as CardSet::evaluate of the canonical cards
+pub fn evaluate(cards: &CardSet) -> i32 {
+    tables().evaluate(cards)
+}
+
+pub fn evaluate_mask(mask: CardMask) -> i32 {
+    tables().evaluate_mask(mask)
+}
@@ -0,0 +1,155 @@
+use crate::action::{Action, LegalActions};
+use crate::card_mask::CardMask;
+use crate::card_set::CardSet;
+use crate::hand_history::{ForcedBet, HandEvent, HandHistory};
+use crate::round_state::{Chips, RoundStage, RoundState};
+use crate::showdown::ShowdownResult;
+use crate::table_config::TableConfig;
+
+
+// What one player knows about a round: the public state, the player's own hole cards and the public history.
+// Other players' hole cards are only in it once they are shown at showdown, so it can be handed to bot code.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlayerView {
+    pub seat: usize,
+    pub hole_cards: CardSet,
+    pub community_cards: CardSet, // Revealed cards only
+    pub player_count: usize,
+    pub button: usize,
+    pub stage: RoundStage,
+    pub turn: Option<usize>, // None once the round is finished or being run out
+    pub folded: u16,
+    pub sitting_out: u16,
+    pub min_raise: Chips,
+    pub config: TableConfig,
+    pub start_chips: Vec<Chips>,
+    pub free_chips: Vec<Chips>,
+    pub bet_chips: Vec<Chips>,
+    pub street_bets: Vec<Chips>,
+    pub legal_actions: Option<LegalActions>, // Only on the player's turn
+    pub history: Option<HandHistory>, // Without the other players' hole cards
+    pub showdown: Option<ShowdownResult>,
+}
+
+impl PlayerView {
+    pub fn new(round_state: &RoundState, seat: usize) -> Self {
+        if seat >= round_state.player_count {
+            panic!("Seat {} does not exist with {} players", seat, round_state.player_count);
+        }
+        let history = round_state.history.as_ref().map(|history| HandHistory {
+            events: history.events.iter()
+                .filter(|event| !matches!(event, HandEvent::HoleCards { seat: other, .. } if *other != seat))
+                .cloned()
+                .collect(),
+        });
+        PlayerView {
+            seat,
+            hole_cards: round_state.player_cards[seat].clone(),
+            community_cards: CardSet::from(round_state.revealed_community_cards()),
+            player_count: round_state.player_count,
+            button: round_state.button as usize,
+            stage: round_state.stage,
+            turn: if round_state.is_finished() || round_state.is_running_out() { None } else { Some(round_state.turn as usize) },
+            folded: round_state.folded,
+            sitting_out: round_state.sitting_out,
+            min_raise: round_state.min_raise,
+            config: round_state.config.clone(),
+            start_chips: round_state.start_chips.clone(),
+            free_chips: round_state.free_chips.clone(),
+            bet_chips: round_state.bet_chips.clone(),
+            street_bets: round_state.street_bets.clone(),
+            legal_actions: round_state.legal_actions().filter(|legal| legal.seat == seat),
+            history,
+            showdown: round_state.showdown.clone(),
+        }
+    }
+
+    pub fn is_my_turn(&self) -> bool {
+        self.turn == Some(self.seat)
+    }
+
+    // Key of the information set for lookups (e.g. CFR): the same for every round where this player saw the same
+    // hole cards, board, stacks, blinds and public actions. It doesn't depend on the order the cards were dealt in,
+    // and it is stable across runs and platforms (FNV-1a over a fixed encoding).
+    // With TableConfig::record_history off only the current state is covered, not how the betting got there.
+    pub fn info_set_key(&self) -> u64 {
+        let mut key = InfoSetKey::new();
+        key.write(self.seat as u64);
+        key.write(self.button as u64);
+        key.write(CardMask::from(&self.hole_cards).0);
+        key.write(CardMask::from(&self.community_cards).0);
+        key.write(self.sitting_out as u64);
+        for &chips in self.start_chips.iter() {
+            key.write(chips);
+        }
+        let config = &self.config;
+        for chips in [config.small_blind, config.big_blind, config.ante, config.big_blind_ante, config.straddle.unwrap_or(0)] {
+            key.write(chips);
+        }
+        match &self.history {
+            Some(history) => {
+                for event in history.events.iter() {
+                    match *event {
+                        HandEvent::Post { seat, forced_bet, amount } => {
+                            key.write(1);
+                            key.write(seat as u64);
+                            key.write(forced_bet_code(forced_bet));
+                            key.write(amount);
+                        }
+                        HandEvent::Action { seat, action, chips_added, .. } => {
+                            key.write(2);
+                            key.write(seat as u64);
+                            // Calls, bets, raises and all-ins only differ in the chips added
+                            key.write(match action {
+                                Action::Fold => 0,
+                                Action::Check => 1,
+                                _ => 2,
+                            });
+                            key.write(chips_added);
+                        }
+                        HandEvent::Street { ref cards, .. } => {
+                            key.write(3);
+                            key.write(CardMask::from(cards).0);
+                        }
+                        HandEvent::HoleCards { .. } => (),
+                    }
+                }
+            }
+            None => {
+                key.write(self.stage as u64);
+                key.write(self.folded as u64);
+                key.write(self.turn.map_or(u64::MAX, |turn| turn as u64));
+                for (&bet, &street_bet) in self.bet_chips.iter().zip(self.street_bets.iter()) {
+                    key.write(bet);
+                    key.write(street_bet);
+                }
+            }
+        }
+        key.0
+    }
+}
+
+struct InfoSetKey(u64);
+
+impl InfoSetKey {
+    fn new() -> Self {
+        InfoSetKey(0xcbf29ce484222325)
+    }
+
+    fn write(&mut self, value: u64) {
+        for byte in value.to_le_bytes() {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+}
+
+fn forced_bet_code(forced_bet: ForcedBet) -> u64 {
+    match forced_bet {
+        ForcedBet::Ante => 0,
+        ForcedBet::SmallBlind => 1,
+        ForcedBet::BigBlind => 2,
+        ForcedBet::BigBlindAnte => 3,
+        ForcedBet::Straddle => 4,
+    }
+}
@@ -0,0 +1,811 @@
+use std::error::Error;
+use std::fmt::{self, Write};
+use crate::action::Action;
+use crate::card::Card;
+use crate::card_mask::CardMask;
+use crate::card_set::CardSet;
+use crate::hand_history::{ForcedBet, HandEvent, HandHistory};
+use crate::hand_rank::{rank_name, HandCategory, HandRank};
+use crate::round_state::{Chips, RoundStage, RoundState};
+use crate::table_config::{BettingStructure, TableConfig};
+
+
+// See: https://www.pokerstars.com/poker/room/hand-history/
+// Amounts are written as plain chips, like play money hands.
+
+// Everything in the header that RoundState doesn't know about
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HandInfo {
+    pub hand_id: u64,
+    pub table_name: String,
+    pub date: String, // e.g. "2024/01/31 20:15:00 ET"
+    pub player_names: Vec<String>, // Per seat, "Player 1", "Player 2", ... when empty
+    pub hero: Option<usize>, // Only the hero's hole cards are written, everyone's when None
+}
+
+impl Default for HandInfo {
+    fn default() -> HandInfo {
+        HandInfo {
+            hand_id: 1,
+            table_name: "Table".to_owned(),
+            date: "1970/01/01 00:00:00 ET".to_owned(),
+            player_names: vec![],
+            hero: None,
+        }
+    }
+}
+
+impl HandInfo {
+    pub fn player_name(&self, seat: usize) -> String {
+        self.player_names.get(seat).filter(|name| !name.is_empty()).cloned().unwrap_or_else(|| format!("Player {}", seat + 1))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportError {
+    NotFinished,
+    NoHistory, // The round was played with TableConfig::record_history off
+}
+
+impl fmt::Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportError::NotFinished => write!(f, "the round is not finished"),
+            ExportError::NoHistory => write!(f, "the round has no hand history"),
+        }
+    }
+}
+
+impl Error for ExportError {}
+
+pub(crate) fn stage_name(stage: RoundStage) -> &'static str {
+    match stage {
+        RoundStage::PreFlop => "Pre-Flop",
+        RoundStage::Flop => "Flop",
+        RoundStage::Turn => "Turn",
+        RoundStage::River => "River",
+        RoundStage::Finished => "Showdown",
+    }
+}
+
+// e.g. "a pair of Kings", "a full house, Kings full of Fives"
+pub(crate) fn hand_description(hand_rank: HandRank) -> String {
+    let ranks = hand_rank.ranks();
+    let name = |i: usize| rank_name(ranks[i], false);
+    let plural = |i: usize| rank_name(ranks[i], true);
+    match hand_rank.category() {
+        HandCategory::HighCard => format!("high card {}", name(0)),
+        HandCategory::Pair => format!("a pair of {}", plural(0)),
+        HandCategory::TwoPair => format!("two pair, {} and {}", plural(0), plural(1)),
+        HandCategory::ThreeOfAKind => format!("three of a kind, {}", plural(0)),
+        HandCategory::Straight => format!("a straight, {} to {}", rank_name((ranks[0] + 9) % 13, false), name(0)),
+        HandCategory::Flush => format!("a flush, {} high", name(0)),
+        HandCategory::FullHouse => format!("a full house, {} full of {}", plural(0), plural(1)),
+        HandCategory::FourOfAKind => format!("four of a kind, {}", plural(0)),
+        HandCategory::StraightFlush if ranks[0] == 12 => "a Royal Flush".to_owned(),
+        HandCategory::StraightFlush => format!("a straight flush, {} to {}", rank_name((ranks[0] + 9) % 13, false), name(0)),
+    }
+}
+
+fn cards_str(cards: &[Card]) -> String {
+    CardSet::from(cards).to_string()
+}
+
+// Writes a finished round as a PokerStars hand history, seats are numbered from 1
+pub fn export_hand(rs: &RoundState, info: &HandInfo) -> Result<String, ExportError> {
+    let showdown = rs.showdown.as_ref().ok_or(ExportError::NotFinished)?;
+    let history = rs.history.as_ref().ok_or(ExportError::NoHistory)?;
+    let name = |seat: usize| info.player_name(seat);
+    let mut out = String::new();
+
+    let (game, stakes) = match rs.config.betting {
+        BettingStructure::NoLimit => ("No Limit", (rs.config.small_blind, rs.config.big_blind)),
+        BettingStructure::PotLimit => ("Pot Limit", (rs.config.small_blind, rs.config.big_blind)),
+        BettingStructure::FixedLimit { small_bet, big_bet, .. } => ("Limit", (small_bet, big_bet)),
+    };
+    writeln!(out, "PokerStars Hand #{}:  Hold'em {} ({}/{}) - {}", info.hand_id, game, stakes.0, stakes.1, info.date).unwrap();
+    writeln!(out, "Table '{}' {}-max Seat #{} is the button", info.table_name, rs.player_count, rs.button + 1).unwrap();
+    for seat in 0..rs.player_count {
+        if rs.start_chips[seat] == 0 {
+            continue;
+        }
+        let sitting_out = if rs.sitting_out & (1 << seat) != 0 { " is sitting out" } else { "" };
+        writeln!(out, "Seat {}: {} ({} in chips){}", seat + 1, name(seat), rs.start_chips[seat], sitting_out).unwrap();
+    }
+
+    // Replay the events to know the bets on every street
+    let mut free_chips = rs.start_chips.clone();
+    let mut street_bets = vec![0; rs.player_count];
+    let mut voluntary = vec![false; rs.player_count];
+    let mut small_blind = None;
+    let mut big_blind = None;
+    let mut folded_on = vec![None; rs.player_count];
+    // The uncalled bet is returned after the last action, before the flop when the forced bets put everyone all-in
+    let uncalled_after = history.events.iter().rposition(|event| matches!(event, HandEvent::Action { .. }))
+        .or_else(|| history.events.iter().rposition(|event| !matches!(event, HandEvent::Street { .. })));
+    let mut hole_cards_written = false;
+    for (i, event) in history.events.iter().enumerate() {
+        match event {
+            HandEvent::Post { seat, forced_bet, amount } => {
+                free_chips[*seat] -= amount;
+                let posts = match forced_bet {
+                    ForcedBet::Ante | ForcedBet::BigBlindAnte => "the ante",
+                    ForcedBet::SmallBlind => {
+                        small_blind = Some(*seat);
+                        "small blind"
+                    }
+                    ForcedBet::BigBlind => {
+                        big_blind = Some(*seat);
+                        "big blind"
+                    }
+                    ForcedBet::Straddle => "straddle",
+                };
+                if forced_bet.is_live() {
+                    street_bets[*seat] += amount;
+                }
+                let all_in = if free_chips[*seat] == 0 { " and is all-in" } else { "" };
+                writeln!(out, "{}: posts {} {}{}", name(*seat), posts, amount, all_in).unwrap();
+            }
+            HandEvent::HoleCards { seat, cards } => {
+                if !hole_cards_written {
+                    writeln!(out, "*** HOLE CARDS ***").unwrap();
+                    hole_cards_written = true;
+                }
+                if info.hero.is_none_or(|hero| hero == *seat) {
+                    writeln!(out, "Dealt to {} [{}]", name(*seat), cards).unwrap();
+                }
+            }
+            HandEvent::Street { stage, cards } => {
+                street_bets.fill(0);
+                let revealed = match stage {
+                    RoundStage::Turn => 3,
+                    RoundStage::River => 4,
+                    _ => 0,
+                };
+                let previous = &rs.community_cards.cards[..revealed];
+                if previous.is_empty() {
+                    writeln!(out, "*** {} *** [{}]", stage_name(*stage).to_uppercase(), cards).unwrap();
+                } else {
+                    writeln!(out, "*** {} *** [{}] [{}]", stage_name(*stage).to_uppercase(), cards_str(previous), cards).unwrap();
+                }
+            }
+            HandEvent::Action { seat, stage, action, chips_added, .. } => {
+                let seat = *seat;
+                let highest_bet = street_bets.iter().copied().max().unwrap_or(0)
+                    .max(if *stage == RoundStage::PreFlop { rs.config.big_blind } else { 0 });
+                let to = street_bets[seat] + chips_added;
+                free_chips[seat] -= chips_added;
+                street_bets[seat] = to;
+                voluntary[seat] |= *chips_added > 0;
+                let line = match action {
+                    Action::Fold => {
+                        folded_on[seat] = Some(*stage);
+                        "folds".to_owned()
+                    }
+                    Action::Check => "checks".to_owned(),
+                    _ if to <= highest_bet => format!("calls {}", chips_added),
+                    _ if highest_bet == 0 => format!("bets {}", chips_added),
+                    _ => format!("raises {} to {}", to - highest_bet, to),
+                };
+                let all_in = if *chips_added > 0 && free_chips[seat] == 0 { " and is all-in" } else { "" };
+                writeln!(out, "{}: {}{}", name(seat), line, all_in).unwrap();
+            }
+        }
+        if Some(i) == uncalled_after {
+            if let Some((seat, amount)) = showdown.uncalled_bet {
+                writeln!(out, "Uncalled bet ({}) returned to {}", amount, name(seat)).unwrap();
+            }
+        }
+    }
+
+    // Side pots are awarded first, from the last one to the main pot
+    if !showdown.uncontested {
+        writeln!(out, "*** SHOW DOWN ***").unwrap();
+        for hand in showdown.hands.iter() {
+            let hole_cards = &hand.cards.cards[..2];
+            writeln!(out, "{}: shows [{}] ({})", name(hand.seat), cards_str(hole_cards), hand_description(hand.hand_rank)).unwrap();
+        }
+    }
+    let mut won = vec![0; rs.player_count];
+    // Side pots are only numbered when there are several: "side pot" or "side pot-1", "side pot-2", ...
+    let side_pot = |i: usize| if showdown.pots.len() > 2 { format!("pot-{}", i) } else { "pot".to_owned() };
+    for (pot_index, pot) in showdown.pots.iter().enumerate().rev() {
+        let pot_name = match (showdown.pots.len(), pot_index) {
+            (1, _) => "pot".to_owned(),
+            (_, 0) => "main pot".to_owned(),
+            (_, i) => format!("side {}", side_pot(i)),
+        };
+        for (&winner, &winnings) in pot.winners.iter().zip(pot.winnings.iter()) {
+            won[winner] += winnings;
+            writeln!(out, "{} collected {} from {}", name(winner), winnings, pot_name).unwrap();
+        }
+    }
+    if showdown.uncontested {
+        if let Some(winner) = (0..rs.player_count).find(|&seat| rs.folded & (1 << seat) == 0) {
+            writeln!(out, "{}: doesn't show hand", name(winner)).unwrap();
+        }
+    }
+
+    writeln!(out, "*** SUMMARY ***").unwrap();
+    let total_pot: Chips = showdown.pots.iter().map(|pot| pot.amount).sum();
+    write!(out, "Total pot {}", total_pot).unwrap();
+    if showdown.pots.len() > 1 {
+        for (i, pot) in showdown.pots.iter().enumerate() {
+            match i {
+                0 => write!(out, " Main pot {}.", pot.amount).unwrap(),
+                i => write!(out, " Side {} {}.", side_pot(i), pot.amount).unwrap(),
+            }
+        }
+    }
+    writeln!(out, " | Rake 0").unwrap();
+    let board = rs.revealed_community_cards();
+    if !board.is_empty() {
+        writeln!(out, "Board [{}]", cards_str(board)).unwrap();
+    }
+    for seat in 0..rs.player_count {
+        if rs.sitting_out & (1 << seat) != 0 {
+            continue;
+        }
+        let mut position = String::new();
+        if seat == rs.button as usize {
+            position += " (button)";
+        }
+        if Some(seat) == small_blind {
+            position += " (small blind)";
+        } else if Some(seat) == big_blind {
+            position += " (big blind)";
+        }
+        let outcome = if let Some(stage) = folded_on[seat] {
+            match stage {
+                RoundStage::PreFlop if !voluntary[seat] && small_blind != Some(seat) && big_blind != Some(seat) =>
+                    "folded before Flop (didn't bet)".to_owned(),
+                RoundStage::PreFlop => "folded before Flop".to_owned(),
+                stage => format!("folded on the {}", stage_name(stage)),
+            }
+        } else if let Some(hand) = showdown.hand(seat) {
+            let hole_cards = cards_str(&hand.cards.cards[..2]);
+            let description = hand_description(hand.hand_rank);
+            if won[seat] > 0 {
+                format!("showed [{}] and won ({}) with {}", hole_cards, won[seat], description)
+            } else {
+                format!("showed [{}] and lost with {}", hole_cards, description)
+            }
+        } else {
+            format!("collected ({})", won[seat])
+        };
+        writeln!(out, "Seat {}: {}{} {}", seat + 1, name(seat), position, outcome).unwrap();
+    }
+    Ok(out)
+}
+
+// --- IMPORT ---
+// Hands are parsed into a HandHistory and replayed through a RoundState.
+// Unknown hole cards (and unrevealed community cards) are filled in with the lowest unused cards.
+
+// Line numbers start at 1
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ImportError {
+    pub line: usize,
+    pub reason: String,
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.reason)
+    }
+}
+
+impl Error for ImportError {}
+
+// A logged amount that differs from what the replay computed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Divergence {
+    pub line: usize,
+    pub reason: String,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.reason)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParsedHand {
+    pub line: usize, // Line of the header
+    pub info: HandInfo,
+    pub config: TableConfig,
+    pub stacks: Vec<Chips>, // Per seat at the start of the hand, 0 for empty seats
+    pub hole_cards: Vec<Option<CardSet>>, // Dealt, shown or mucked
+    pub board: CardSet, // Revealed community cards
+    pub history: HandHistory,
+    pub event_lines: Vec<usize>, // Line of every history event
+    pub uncalled_bet: Option<(usize, Chips, usize)>, // (seat, chips, line)
+    pub collected: Vec<(usize, Chips, usize)>, // (seat, chips, line)
+    pub total_pot: Option<(Chips, usize)>, // (chips, line)
+    pub rake: Chips,
+}
+
+#[derive(Debug)]
+pub struct Replay {
+    pub round: RoundState,
+    pub divergences: Vec<Divergence>,
+}
+
+// Parses every hand in the text, hands start with a "PokerStars Hand #" header
+pub fn parse_hands(text: &str) -> Vec<Result<ParsedHand, ImportError>> {
+    let mut hands: Vec<Vec<(usize, &str)>> = vec![];
+    let mut results = vec![];
+    // Files saved on Windows may start with a byte order mark
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.starts_with("PokerStars Hand #") {
+            hands.push(vec![]);
+        }
+        match hands.last_mut() {
+            Some(lines) if !line.is_empty() => lines.push((i + 1, line)),
+            None if !line.is_empty() && results.is_empty() => {
+                results.push(Err(ImportError { line: i + 1, reason: "expected a 'PokerStars Hand #' header".to_owned() }));
+            }
+            _ => (),
+        }
+    }
+    results.extend(hands.iter().map(|lines| parse_lines(lines)));
+    results
+}
+
+pub fn parse_hand(text: &str) -> Result<ParsedHand, ImportError> {
+    let mut hands = parse_hands(text).into_iter();
+    match (hands.next(), hands.next()) {
+        (None, _) => Err(ImportError { line: 1, reason: "no hand found".to_owned() }),
+        (Some(hand), None) => hand,
+        (Some(_), Some(second)) => Err(ImportError {
+            line: second.map_or_else(|e| e.line, |hand| hand.line),
+            reason: "more than one hand, use parse_hands".to_owned(),
+        }),
+    }
+}
+
+// "1500", "$0.50" (in cents), "1,000"
+fn parse_amount(s: &str, cents: bool) -> Option<Chips> {
+    let s: String = s.trim_start_matches(['$', '€', '£']).chars().filter(|&c| c != ',').collect();
+    let (whole, fraction) = s.split_once('.').unwrap_or((&s, ""));
+    if whole.is_empty() || !whole.chars().all(|c| c.is_ascii_digit()) || !fraction.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let whole: Chips = whole.parse().ok()?;
+    if !cents {
+        return if fraction.trim_end_matches('0').is_empty() { Some(whole) } else { None };
+    }
+    if fraction.len() > 2 {
+        return None;
+    }
+    let fraction: Chips = format!("{:0<2}", fraction).parse().ok()?;
+    Some(whole * 100 + fraction)
+}
+
+// Cards between the last pair of brackets, e.g. "[2c 7d 9h] [Js]" -> "Js"
+fn parse_bracketed_cards(s: &str) -> Option<CardSet> {
+    let start = s.rfind('[')?;
+    let end = start + s[start..].find(']')?;
+    s[start + 1..end].parse().ok()
+}
+
+fn first_bracketed_cards(s: &str) -> Option<CardSet> {
+    let start = s.find('[')?;
+    let end = start + s[start..].find(']')?;
+    s[start + 1..end].parse().ok()
+}
+
+fn parse_lines(lines: &[(usize, &str)]) -> Result<ParsedHand, ImportError> {
+    let error = |line: usize, reason: String| ImportError { line, reason };
+    let (header_line, header) = lines[0];
+
+    // PokerStars Hand #123:  Hold'em No Limit ($0.50/$1.00 USD) - 2024/01/31 20:15:00 ET
+    let rest = &header["PokerStars Hand #".len()..];
+    let id_end = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+    let hand_id = rest[..id_end].parse().map_err(|_| error(header_line, "missing hand number".to_owned()))?;
+    if !header.contains("Hold'em") {
+        return Err(error(header_line, "only Hold'em hands are supported".to_owned()));
+    }
+    let stakes = header.match_indices('(')
+        .filter_map(|(start, _)| {
+            let content = &header[start + 1..start + header[start..].find(')')?];
+            let (small, big) = content.split(' ').next()?.split_once('/')?;
+            let cents = small.starts_with(['$', '€', '£']);
+            Some((parse_amount(small, cents)?, parse_amount(big, cents)?, cents))
+        })
+        .next();
+    let (small_stake, big_stake, cents) = stakes.ok_or_else(|| error(header_line, "missing stakes, e.g. '(50/100)'".to_owned()))?;
+    let betting = if header.contains("Pot Limit") {
+        BettingStructure::PotLimit
+    } else if header.contains("No Limit") {
+        BettingStructure::NoLimit
+    } else if header.contains("Limit") {
+        BettingStructure::FixedLimit { small_bet: small_stake, big_bet: big_stake, max_bets: 4 }
+    } else {
+        return Err(error(header_line, "unknown betting structure".to_owned()));
+    };
+    let date = header.rsplit_once(" - ").map_or("", |(_, date)| date).to_owned();
+
+    // Table 'Name' 6-max Seat #1 is the button
+    let &(table_line, table) = lines.get(1).ok_or_else(|| error(header_line, "missing table line".to_owned()))?;
+    let table_name = table.strip_prefix("Table '")
+        .and_then(|rest| rest.split_once('\''))
+        .map(|(name, _)| name.to_owned())
+        .ok_or_else(|| error(table_line, "expected \"Table '<name>'\"".to_owned()))?;
+    let max_seats: Option<usize> = table.split(' ')
+        .find_map(|token| token.strip_suffix("-max"))
+        .and_then(|max| max.parse().ok());
+    let button_seat: usize = table.split_once("Seat #")
+        .and_then(|(_, rest)| rest.split(' ').next())
+        .and_then(|seat| seat.parse().ok())
+        .filter(|&seat| seat > 0)
+        .ok_or_else(|| error(table_line, "missing button seat".to_owned()))?;
+
+    // Seats, numbered from 1
+    let mut seats: Vec<(usize, String, Chips, bool)> = vec![];
+    let mut index = 2;
+    while let Some(&(line, text)) = lines.get(index) {
+        let Some(rest) = text.strip_prefix("Seat ") else { break };
+        let (number, rest) = rest.split_once(": ").ok_or_else(|| error(line, "expected 'Seat <n>: <name> (<chips> in chips)'".to_owned()))?;
+        let number: usize = number.parse().ok().filter(|&n| n > 0).ok_or_else(|| error(line, format!("invalid seat '{}'", number)))?;
+        let (name, chips) = rest.rsplit_once(" (").ok_or_else(|| error(line, "missing stack".to_owned()))?;
+        let (chips, tail) = chips.split_once(" in chips").ok_or_else(|| error(line, "missing stack".to_owned()))?;
+        let chips = parse_amount(chips, cents).ok_or_else(|| error(line, format!("invalid amount '{}'", chips)))?;
+        seats.push((number - 1, name.to_owned(), chips, tail.contains("is sitting out")));
+        index += 1;
+    }
+    let player_count = max_seats.unwrap_or(0).max(seats.iter().map(|seat| seat.0 + 1).max().unwrap_or(0)).max(button_seat);
+    if player_count > 16 {
+        return Err(error(table_line, "more than 16 seats".to_owned()));
+    }
+    let mut player_names = vec![String::new(); player_count];
+    let mut stacks = vec![0; player_count];
+    // Empty seats sit out
+    let mut sitting_out = ((1u32 << player_count) - 1) as u16;
+    for (seat, name, chips, is_sitting_out) in seats.iter() {
+        player_names[*seat] = name.clone();
+        stacks[*seat] = *chips;
+        if !*is_sitting_out {
+            sitting_out &= !(1 << seat);
+        }
+    }
+    let mut names: Vec<(usize, &str)> = seats.iter().map(|(seat, name, _, _)| (*seat, name.as_str())).collect();
+    names.sort_by_key(|(_, name)| std::cmp::Reverse(name.len()));
+    // "<name>: <rest>"
+    let player_line = |text: &'_ str| -> Option<(usize, String)> {
+        names.iter().find_map(|&(seat, name)| text.strip_prefix(name)?.strip_prefix(": ").map(|rest| (seat, rest.to_owned())))
+    };
+    let find_seat = |name: &str| names.iter().find(|&&(_, n)| n == name).map(|&(seat, _)| seat);
+
+    let mut history = HandHistory::default();
+    let mut event_lines = vec![];
+    let mut hole_cards: Vec<Option<CardSet>> = vec![None; player_count];
+    let mut board: Vec<Card> = vec![];
+    let mut stage = RoundStage::PreFlop;
+    let mut street_bets: Vec<Chips> = vec![0; player_count];
+    let mut pot: Chips = 0;
+    let mut uncalled_bet = None;
+    let mut collected = vec![];
+    let mut total_pot = None;
+    let mut rake = 0;
+    let mut in_summary = false;
+    let mut dealt_to = 0u16; // Seats with a 'Dealt to' line
+    let (mut small_blind, mut ante, mut straddle) = (None, 0, None);
+
+    for &(line, text) in &lines[index..] {
+        let amount = |s: &str| parse_amount(s, cents).ok_or_else(|| error(line, format!("invalid amount '{}'", s)));
+        if let Some(marker) = text.strip_prefix("*** ") {
+            let next_stage = match marker.split(" ***").next().unwrap_or("") {
+                "HOLE CARDS" | "SHOW DOWN" => None,
+                "FLOP" => Some(RoundStage::Flop),
+                "TURN" => Some(RoundStage::Turn),
+                "RIVER" => Some(RoundStage::River),
+                "SUMMARY" => {
+                    in_summary = true;
+                    None
+                }
+                other => return Err(error(line, format!("unsupported section '{}'", other))),
+            };
+            if let Some(next_stage) = next_stage {
+                let cards = parse_bracketed_cards(text).ok_or_else(|| error(line, "missing street cards".to_owned()))?;
+                board.extend(cards.iter().copied());
+                history.push(HandEvent::Street { stage: next_stage, cards });
+                event_lines.push(line);
+                street_bets.fill(0);
+                stage = next_stage;
+            }
+            continue;
+        }
+        if in_summary {
+            if let Some(rest) = text.strip_prefix("Total pot ") {
+                let (total, rest) = rest.split_once(' ').unwrap_or((rest, ""));
+                total_pot = Some((amount(total)?, line));
+                if let Some((_, rake_amount)) = rest.split_once("| Rake ") {
+                    rake = amount(rake_amount.split(' ').next().unwrap_or(""))?;
+                }
+            } else if let Some(rest) = text.strip_prefix("Seat ") {
+                // Seat 2: Bob (big blind) showed [Kd Kc] and lost with a pair of Kings
+                let seat = rest.split_once(':').and_then(|(number, _)| number.parse::<usize>().ok()).filter(|&n| n > 0 && n <= player_count);
+                if let (Some(seat), Some(cards)) = (seat, first_bracketed_cards(rest)) {
+                    hole_cards[seat - 1].get_or_insert(cards);
+                }
+            }
+            continue;
+        }
+        if let Some(rest) = text.strip_prefix("Dealt to ") {
+            let (name, cards) = rest.split_once(" [").ok_or_else(|| error(line, "missing hole cards".to_owned()))?;
+            let seat = find_seat(name).ok_or_else(|| error(line, format!("unknown player '{}'", name)))?;
+            let cards = first_bracketed_cards(rest).filter(|cards| cards.len() == 2)
+                .ok_or_else(|| error(line, format!("invalid hole cards '[{}'", cards)))?;
+            dealt_to |= 1 << seat;
+            hole_cards[seat] = Some(cards.clone());
+            history.push(HandEvent::HoleCards { seat, cards });
+            event_lines.push(line);
+            continue;
+        }
+        if let Some(rest) = text.strip_prefix("Uncalled bet (") {
+            let (chips, name) = rest.split_once(") returned to ").ok_or_else(|| error(line, "expected 'Uncalled bet (<chips>) returned to <name>'".to_owned()))?;
+            let seat = find_seat(name).ok_or_else(|| error(line, format!("unknown player '{}'", name)))?;
+            uncalled_bet = Some((seat, amount(chips)?, line));
+            continue;
+        }
+        if let Some((seat, rest)) = names.iter().find_map(|&(seat, name)| text.strip_prefix(name)?.strip_prefix(" collected ").map(|rest| (seat, rest))) {
+            let chips = rest.split(' ').next().unwrap_or("");
+            collected.push((seat, amount(chips)?, line));
+            continue;
+        }
+        let Some((seat, rest)) = player_line(text) else {
+            // Chat and table messages, e.g. "Bob said, ..." or "Bob joins the table at seat #3"
+            if names.iter().any(|&(_, name)| text.starts_with(name)) {
+                continue;
+            }
+            return Err(error(line, "unrecognized line".to_owned()));
+        };
+        // All-ins are replayed as the call, bet or raise they amount to
+        let all_in = rest.ends_with(" and is all-in");
+        let rest = rest.strip_suffix(" and is all-in").unwrap_or(&rest);
+        let mut words = rest.split(' ');
+        let verb = words.next().unwrap_or("");
+        let forced_bet = match rest {
+            _ if rest.starts_with("posts small & big blinds") => return Err(error(line, "dead blinds are not supported".to_owned())),
+            _ if rest.starts_with("posts small blind ") => Some(ForcedBet::SmallBlind),
+            _ if rest.starts_with("posts big blind ") => Some(ForcedBet::BigBlind),
+            _ if rest.starts_with("posts the ante ") => Some(ForcedBet::Ante),
+            _ if rest.starts_with("posts straddle ") => Some(ForcedBet::Straddle),
+            _ => None,
+        };
+        if let Some(forced_bet) = forced_bet {
+            let chips = amount(rest.rsplit(' ').next().unwrap_or(""))?;
+            // A blind posted all-in can be short, the stakes come from the header then
+            match forced_bet {
+                ForcedBet::SmallBlind if !all_in => small_blind = Some(chips),
+                ForcedBet::SmallBlind | ForcedBet::BigBlind => {}
+                ForcedBet::Straddle => straddle = Some(if all_in { 2 * big_stake } else { chips }),
+                _ => ante = ante.max(chips),
+            }
+            if forced_bet.is_live() {
+                street_bets[seat] += chips;
+            }
+            pot += chips;
+            history.push(HandEvent::Post { seat, forced_bet, amount: chips });
+            event_lines.push(line);
+            continue;
+        }
+        let (action, chips_added) = match verb {
+            "folds" => (Action::Fold, 0),
+            "checks" => (Action::Check, 0),
+            "calls" => (Action::Call, amount(words.next().unwrap_or(""))?),
+            "bets" => {
+                let chips = amount(words.next().unwrap_or(""))?;
+                (Action::Bet(street_bets[seat] + chips), chips)
+            }
+            "raises" => {
+                let to = match (words.next(), words.next(), words.next()) {
+                    (Some(_), Some("to"), Some(to)) => amount(to)?,
+                    _ => return Err(error(line, "expected 'raises <chips> to <chips>'".to_owned())),
+                };
+                if to <= street_bets[seat] {
+                    return Err(error(line, format!("raise to {} is not a raise", to)));
+                }
+                (Action::Raise(to), to - street_bets[seat])
+            }
+            "shows" | "mucks" => {
+                if let Some(cards) = first_bracketed_cards(rest) {
+                    hole_cards[seat].get_or_insert(cards);
+                }
+                continue;
+            }
+            // Table messages about the player
+            "doesn't" | "is" | "has" | "sits" | "leaves" | "joins" | "will" | "said," | "timed" => continue,
+            _ => return Err(error(line, format!("unrecognized action '{}'", rest))),
+        };
+        street_bets[seat] += chips_added;
+        pot += chips_added;
+        history.push(HandEvent::Action { seat, stage, action, chips_added, pot });
+        event_lines.push(line);
+    }
+
+    let (small_blind, big_blind) = match betting {
+        BettingStructure::FixedLimit { small_bet, .. } => (small_blind.unwrap_or(small_bet / 2), small_bet),
+        _ => (small_stake, big_stake),
+    };
+
+    // Blind seats from the table layout, the way RoundState assigns them
+    let button = button_seat - 1;
+    let is_dealt_in = |seat: usize| stacks[seat] > 0 && sitting_out & (1 << seat) == 0;
+    let dealt_in = (0..player_count).filter(|&i| is_dealt_in(i)).count();
+    let next_dealt_in = |seat: usize| (1..=player_count).map(|i| (seat + i) % player_count).find(|&i| is_dealt_in(i)).unwrap_or(seat);
+    let first_after_button = next_dealt_in(button);
+    let ante_seats: Vec<(usize, Chips)> = history.events.iter()
+        .filter_map(|event| match *event {
+            HandEvent::Post { seat, forced_bet: ForcedBet::Ante, amount } => Some((seat, amount)),
+            _ => None,
+        })
+        .collect();
+    let posted = |seat: usize, blind: ForcedBet| history.events.iter()
+        .any(|event| matches!(*event, HandEvent::Post { seat: s, forced_bet, .. } if s == seat && forced_bet == blind));
+    let chips_left = |seat: usize| ante_seats.iter().filter(|&&(s, _)| s == seat).map(|&(_, chips)| chips).sum::<Chips>() < stacks[seat];
+    // The small blind is dead when the first player after the button doesn't post it although they could,
+    // or when the next player doesn't post the big blind although they could
+    let second_after_button = next_dealt_in(first_after_button);
+    let dead_small_blind = dealt_in > 2
+        && !posted(first_after_button, ForcedBet::SmallBlind)
+        && (chips_left(first_after_button) || (chips_left(second_after_button) && !posted(second_after_button, ForcedBet::BigBlind)));
+    let small_blind_seat = if dealt_in == 2 && is_dealt_in(button) { button } else { first_after_button };
+    let big_blind_seat = if dead_small_blind { first_after_button } else { next_dealt_in(small_blind_seat) };
+    // Only the big blind posting an ante is a big blind ante
+    let big_blind_ante = ante_seats.len() == 1 && ante_seats[0].0 == big_blind_seat;
+    if big_blind_ante {
+        for event in history.events.iter_mut() {
+            if let HandEvent::Post { forced_bet, .. } = event {
+                if *forced_bet == ForcedBet::Ante {
+                    *forced_bet = ForcedBet::BigBlindAnte;
+                }
+            }
+        }
+    }
+    let config = TableConfig {
+        small_blind,
+        big_blind,
+        ante: if big_blind_ante { 0 } else { ante },
+        big_blind_ante: if big_blind_ante { ante } else { 0 },
+        straddle,
+        button,
+        sitting_out,
+        dead_small_blind,
+        betting,
+        record_history: true,
+        step_run_out: false,
+    };
+
+    // Without a hero every seat gets a 'Dealt to' line
+    let hero = (dealt_to.count_ones() == 1).then(|| dealt_to.trailing_zeros() as usize);
+    Ok(ParsedHand {
+        line: header_line,
+        info: HandInfo { hand_id, table_name, date, player_names, hero },
+        config,
+        stacks,
+        hole_cards,
+        board: CardSet::from(&board[..]),
+        history,
+        event_lines,
+        uncalled_bet,
+        collected,
+        total_pot,
+        rake,
+    })
+}
+
+// Replays the logged actions, illegal actions stop the replay.
+// Logged amounts are compared with the computed ones, per player results only when there is no rake.
+pub fn replay(hand: &ParsedHand) -> Result<Replay, ImportError> {
+    let error = |line: usize, reason: String| ImportError { line, reason };
+    let name = |seat: usize| hand.info.player_name(seat);
+    let player_count = hand.stacks.len();
+    let dealt_in: Vec<usize> = (0..player_count)
+        .filter(|&i| hand.stacks[i] > 0 && hand.config.sitting_out & (1 << i) == 0)
+        .collect();
+    if dealt_in.len() < 2 {
+        return Err(error(hand.line, "need atleast 2 players with chips".to_owned()));
+    }
+
+    // Fill in the unknown cards
+    let mut used = CardMask::from(&hand.board);
+    for cards in hand.hole_cards.iter().flatten() {
+        let cards = CardMask::from(cards);
+        if !used.is_disjoint(cards) {
+            return Err(error(hand.line, "the same card is dealt more than once".to_owned()));
+        }
+        used |= cards;
+    }
+    let mut unused = (!used).iter();
+    let player_cards: Vec<CardSet> = (0..player_count)
+        .map(|seat| match (&hand.hole_cards[seat], dealt_in.contains(&seat)) {
+            (_, false) => CardSet::new(&[]),
+            (Some(cards), true) => cards.clone(),
+            (None, true) => CardSet::new(&[unused.next().unwrap(), unused.next().unwrap()]),
+        })
+        .collect();
+    let mut board: Vec<Card> = hand.board.iter().copied().collect();
+    board.extend(unused.by_ref().take(5 - board.len()));
+
+    let mut round = RoundState::with_config_and_cards(hand.config.clone(), hand.stacks.clone(), player_cards, board[..].into());
+    let mut divergences = vec![];
+
+    // Forced bets
+    let mut posts: Vec<HandEvent> = round.history.as_ref().map_or(vec![], |history| {
+        history.events.iter().filter(|event| matches!(event, HandEvent::Post { .. })).cloned().collect()
+    });
+    for (event, &line) in hand.history.events.iter().zip(hand.event_lines.iter()) {
+        if let HandEvent::Post { seat, forced_bet, amount } = event {
+            match posts.iter().position(|post| post == event) {
+                Some(i) => {
+                    posts.remove(i);
+                }
+                None => divergences.push(Divergence {
+                    line,
+                    reason: format!("{} posted {} ({:?}), which the table config doesn't expect", name(*seat), amount, forced_bet),
+                }),
+            }
+        }
+    }
+    for post in posts {
+        if let HandEvent::Post { seat, forced_bet, amount } = post {
+            divergences.push(Divergence { line: hand.line, reason: format!("{} should have posted {} ({:?})", name(seat), amount, forced_bet) });
+        }
+    }
+
+    // Actions
+    let mut last_line = hand.line;
+    for (event, &line) in hand.history.events.iter().zip(hand.event_lines.iter()) {
+        let HandEvent::Action { seat, action, chips_added, pot, .. } = *event else {
+            continue;
+        };
+        last_line = line;
+        let legal = round.legal_actions().ok_or_else(|| error(line, "action after the round is finished".to_owned()))?;
+        if legal.seat != seat {
+            return Err(error(line, format!("{} acted out of turn, it is {}'s turn", name(seat), name(legal.seat))));
+        }
+        let outcome = round.try_apply(seat, action).map_err(|e| error(line, format!("{}: {}", name(seat), e)))?;
+        if outcome.chips_added != chips_added {
+            divergences.push(Divergence { line, reason: format!("{} put in {}, replay computed {}", name(seat), chips_added, outcome.chips_added) });
+        } else if !round.is_finished() && round.bet_chips.iter().sum::<Chips>() != pot {
+            divergences.push(Divergence { line, reason: format!("pot is {}, replay computed {}", pot, round.bet_chips.iter().sum::<Chips>()) });
+        }
+    }
+    let showdown = match round.showdown.as_ref() {
+        Some(showdown) => showdown,
+        None => return Err(error(last_line, "the hand ends before the round is finished".to_owned())),
+    };
+
+    // Results
+    let logged_uncalled = hand.uncalled_bet.map(|(seat, chips, _)| (seat, chips));
+    if logged_uncalled != showdown.uncalled_bet {
+        let line = hand.uncalled_bet.map_or(last_line, |(_, _, line)| line);
+        divergences.push(Divergence { line, reason: format!("uncalled bet {:?}, replay computed {:?}", logged_uncalled, showdown.uncalled_bet) });
+    }
+    let computed_pot: Chips = showdown.pots.iter().map(|pot| pot.amount).sum();
+    if let Some((total_pot, line)) = hand.total_pot {
+        if total_pot != computed_pot {
+            divergences.push(Divergence { line, reason: format!("total pot is {}, replay computed {}", total_pot, computed_pot) });
+        }
+    }
+    if hand.rake == 0 {
+        for seat in 0..player_count {
+            let logged: Chips = hand.collected.iter().filter(|c| c.0 == seat).map(|c| c.1).sum();
+            let computed: Chips = showdown.pots.iter()
+                .flat_map(|pot| pot.winners.iter().zip(pot.winnings.iter()))
+                .filter(|&(&winner, _)| winner == seat)
+                .map(|(_, &winnings)| winnings)
+                .sum();
+            if logged != computed {
+                let line = hand.collected.iter().find(|c| c.0 == seat).map_or(hand.total_pot.map_or(last_line, |t| t.1), |c| c.2);
+                divergences.push(Divergence { line, reason: format!("{} collected {}, replay computed {}", name(seat), logged, computed) });
+            }
+        }
+    }
+    Ok(Replay { round, divergences })
+}
@@ -0,0 +1,111 @@
+use std::fmt;
+use std::slice::Iter;
+use crate::round_state::Chips;
+
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Pot {
+    pub amount: Chips,
+    pub eligible: u16, // Bitmask of the players that can win this pot
+}
+
+impl Pot {
+    pub fn is_eligible(&self, seat: usize) -> bool {
+        self.eligible & (1 << seat) != 0
+    }
+}
+
+impl fmt::Display for Pot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let seats: Vec<String> = (0..16).filter(|&i| self.is_eligible(i)).map(|i| i.to_string()).collect();
+        write!(f, "{} ({})", self.amount, seats.join(", "))
+    }
+}
+
+// Main pot followed by the side pots, built from what every player put in.
+// Every all-in amount of a player still in the hand caps a pot, players that can still bet are
+// eligible for all pots. Folded players' chips count towards the pots but they can't win them.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Pots {
+    pub pots: Vec<Pot>,
+}
+
+impl Pots {
+    pub fn new(contributions: &[Chips], folded: u16, all_in: u16) -> Self {
+        let in_hand = |i: usize| folded & (1 << i) == 0;
+        let highest = contributions.iter().enumerate()
+            .filter(|&(i, _)| in_hand(i))
+            .map(|(_, &chips)| chips)
+            .max()
+            .unwrap_or(0);
+        let mut levels: Vec<Chips> = contributions.iter().enumerate()
+            .filter(|&(i, &chips)| in_hand(i) && chips > 0 && (all_in & (1 << i) != 0 || chips == highest))
+            .map(|(_, &chips)| chips)
+            .collect();
+        levels.sort_unstable();
+        levels.dedup();
+
+        let mut pots = Vec::with_capacity(levels.len());
+        let mut previous_level = 0;
+        for level in levels {
+            let amount = contributions.iter()
+                .map(|&chips| Chips::min(chips, level) - Chips::min(chips, previous_level))
+                .sum();
+            let eligible = contributions.iter().enumerate()
+                .filter(|&(i, &chips)| in_hand(i) && (chips >= level || all_in & (1 << i) == 0))
+                .fold(0u16, |mask, (i, _)| mask | (1 << i));
+            pots.push(Pot { amount, eligible });
+            previous_level = level;
+        }
+
+        // Folded chips above the highest level of the players still in the hand
+        let dead_chips: Chips = contributions.iter().map(|&chips| chips.saturating_sub(previous_level)).sum();
+        if dead_chips > 0 {
+            match pots.last_mut() {
+                Some(pot) => pot.amount += dead_chips,
+                None => pots.push(Pot { amount: dead_chips, eligible: 0 }),
+            }
+        }
+        Pots { pots }
+    }
+
+    pub fn main_pot(&self) -> Option<&Pot> {
+        self.pots.first()
+    }
+
+    pub fn side_pots(&self) -> &[Pot] {
+        self.pots.get(1..).unwrap_or(&[])
+    }
+
+    pub fn total(&self) -> Chips {
+        self.pots.iter().map(|pot| pot.amount).sum()
+    }
+
+    pub fn len(&self) -> usize {
+        self.pots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pots.is_empty()
+    }
+
+    pub fn iter(&self) -> Iter<'_, Pot> {
+        self.pots.iter()
+    }
+}
+
+// e.g. "main 350 (0, 1, 2), side 400 (1, 2)"
+impl fmt::Display for Pots {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "empty");
+        }
+        for (i, pot) in self.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{} {}", if i == 0 {"main"} else {"side"}, pot)?;
+        }
+        Ok(())
+    }
+}
@@ -7,11 +7,27 @@
 //
 // --- BET SIZE ---
 // Min bet amount: max(big blind, highest raise seen this round).
+// An all-in for less than a full raise doesn't reopen the betting: players who already acted on this street
+// may only call or fold, unless the bet they face grew by at least a full raise since they last acted.
 //
 // --- 2-player game ---
 // The normal rules for positioning the blinds do not apply when there are only two players at the table.
 // The player on the button is always due the small blind, and the other player must pay the big blind.
 // The player on the button is therefore the first to act before the flop, but last to act for all remaining betting rounds.
+// When the button seat is sitting out, the small blind (the first player after it) takes the role of the button.
+//
+// --- FORCED BETS ---
+// Antes (and the big blind ante) are posted first and are dead money, they don't count towards calling a bet.
+// A player who can't cover a forced bet posts what they have and is all-in.
+// With a dead small blind (see Table) the first player after the button posts the big blind and no small blind is posted.
+// Pre-flop, players always have to call the full big blind, even if the big blind is all-in for less.
+// A straddle is a live blind by the player left of the big blind: action starts left of the straddler
+// and the straddler acts last pre-flop.
+//
+// --- END OF THE ROUND ---
+// The round ends as soon as all but one player folded, that player wins the pot without showing their cards.
+// The part of a bet that nobody called is returned before the pot is awarded.
+// Once at most one player can still bet and has nothing left to call, the remaining streets are dealt without betting.
 //
 // --- SPLITS ---
 // https://www.rookieroad.com/poker/how-do-you-split-the-pot-in-a-poker-game/
@@ -19,12 +35,22 @@
 use core::panic;
 use std::vec;
 use std::fmt;
-use std::cmp::Ordering::Equal;
 
-use rand::prelude::*;
-use crate::{card::Card, card_set::CardSet};
+use rand::Rng;
+use crate::{action::{Action, ActionError, ActionOutcome, LegalActions}, card::{Card, Deck}, card_mask::CardMask, card_set::CardSet};
+use crate::hand_history::{ForcedBet, HandEvent, HandHistory};
+use crate::hand_rank::HandRank;
+use crate::lookup_eval;
+use crate::player_view::PlayerView;
+use crate::pots::Pots;
+use crate::showdown::{PotResult, ShowdownResult, ShownHand};
+use crate::table_config::{BettingStructure, TableConfig};
+
+// Chip amounts are integers in the smallest unit, so pots split without rounding errors.
+// The f32 based functions (RoundState::new, do_action) are denominated in big blinds.
+pub type Chips = u64;
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum RoundStage {
     PreFlop,
     Flop,
@@ -50,17 +76,24 @@ pub struct RoundState {
     pub community_cards: CardSet,
     pub button: u8,
     pub folded: u16, // Bitmask of players who have folded.
+    pub sitting_out: u16, // Bitmask of players who are not dealt in, they are also marked as folded.
     pub stage: RoundStage,
-    pub min_raise: f32,
+    pub min_raise: Chips,
     pub last_raise_by: u8,
     pub turn: u8,
+    pub config: TableConfig,
+    pub showdown: Option<ShowdownResult>, // Set once the round is finished
+    pub run_out_from: Option<RoundStage>, // First street dealt without betting, set once betting is closed
+    pub history: Option<HandHistory>, // None when TableConfig::record_history is off
 
     // Player count dependent
     pub player_count: usize,
     pub player_cards: Vec<CardSet>, // TODO: this is not ideal to use CardSet here ...
-    pub bet_chips: Vec<f32>,
-    pub start_chips: Vec<f32>,
-    pub free_chips: Vec<f32>,
+    pub bet_chips: Vec<Chips>, // Total chips put in the pot this round
+    pub street_bets: Vec<Chips>, // Chips bet on the current street, excludes antes
+    pub street_actions: Vec<(usize, Chips)>, // (seat, highest street bet after acting) for every action on the current street
+    pub start_chips: Vec<Chips>,
+    pub free_chips: Vec<Chips>,
 }
 
 impl fmt::Display for RoundState {
@@ -75,18 +108,19 @@ impl fmt::Display for RoundState {
         for i in 0..self.player_count {
             write!(
                 fmt,
-                "\n    [Player {}, '{}', {:5.3}/{:5.3}] [{}|{}|{}|{}]",
+                "\n    [Player {}, '{}', {}/{}] [{}|{}|{}|{}|{}]",
                 i,
                 self.player_cards[i],
                 self.bet_chips[i],
                 self.free_chips[i],
-                if self.folded & (1 << i) == 1 {"FO"} else {"  "},
+                if self.sitting_out & (1 << i) != 0 {"SO"} else {"  "},
+                if self.folded & (1 << i) != 0 {"FO"} else {"  "},
                 if i as u8 == self.button {"BU"} else {"  "},
                 if i as u8 == self.turn {"TU"} else {"  "},
                 if i as u8 == self.last_raise_by {"LR"} else {"  "},
             )?;
         }
-        write!(fmt, "\n)")?;
+        write!(fmt, "\n  pots: {}\n)", self.pots())?;
         Ok(())
     }
 }
@@ -98,74 +132,236 @@ impl Default for RoundState {
             community_cards: "".into(),
             player_cards: vec![],
             bet_chips: vec![],
+            street_bets: vec![],
+            street_actions: vec![],
             start_chips: vec![],
             free_chips: vec![],
             
             stage: RoundStage::PreFlop,
             turn: 0,
             button: 0,
-            min_raise: 1.0,
+            min_raise: TableConfig::default().big_blind,
+            config: TableConfig::default(),
+            showdown: None,
+            run_out_from: None,
+            history: None,
             folded: 0,
+            sitting_out: 0,
             last_raise_by: 0,
         }
     }
 }
 
+// Seats that are sitting out or have no chips left are not dealt in
+fn sitting_out_mask(config: &TableConfig, free_chips: &[Chips]) -> u16 {
+    free_chips.iter().enumerate()
+        .filter(|&(i, &chips)| chips == 0 || config.sitting_out & (1 << i) != 0)
+        .fold(0, |mask, (i, _)| mask | (1 << i))
+}
+
 impl RoundState {
+    // Deals from a deck shuffled with the thread rng, free_chips are in big blinds
     pub fn new(free_chips: Vec<f32>) -> Self {
+        let config = TableConfig::default();
+        RoundState::from_chips(free_chips.into_iter().map(|bb| config.big_blinds_to_chips(bb)).collect())
+    }
+
+    // Deals from a deck shuffled with the thread rng
+    pub fn from_chips(free_chips: Vec<Chips>) -> Self {
+        RoundState::from_deck(free_chips, &mut Deck::new())
+    }
+
+    pub fn from_seed(free_chips: Vec<Chips>, seed: u64) -> Self {
+        RoundState::from_deck(free_chips, &mut Deck::from_seed(seed))
+    }
+
+    pub fn from_rng<R: Rng + ?Sized>(free_chips: Vec<Chips>, rng: &mut R) -> Self {
+        RoundState::from_deck(free_chips, &mut Deck::from_rng(rng))
+    }
+
+    pub fn from_deck(free_chips: Vec<Chips>, deck: &mut Deck) -> Self {
+        RoundState::with_config(TableConfig::default(), free_chips, deck)
+    }
+
+    // Uses the given hole cards (2 per player) and community cards (5), e.g. to replay a recorded hand
+    pub fn from_cards(free_chips: Vec<Chips>, player_cards: Vec<CardSet>, community_cards: CardSet) -> Self {
+        RoundState::with_config_and_cards(TableConfig::default(), free_chips, player_cards, community_cards)
+    }
+
+    // Deals the 5 community cards first, followed by 2 cards for each player that is not sitting out
+    pub fn with_config(config: TableConfig, free_chips: Vec<Chips>, deck: &mut Deck) -> Self {
+        let sitting_out = sitting_out_mask(&config, &free_chips);
+        let community_cards: CardSet = deck.deal(5).into();
+        let player_cards = (0..free_chips.len())
+            .map(|i| if sitting_out & (1 << i) == 0 { deck.deal(2).into() } else { CardSet::new(&[]) })
+            .collect();
+        RoundState::with_config_and_cards(config, free_chips, player_cards, community_cards)
+    }
+
+    // Hole cards of players that are sitting out are ignored
+    pub fn with_config_and_cards(
+        config: TableConfig,
+        free_chips: Vec<Chips>,
+        mut player_cards: Vec<CardSet>,
+        community_cards: CardSet
+    ) -> Self {
         let player_count = free_chips.len();
-        if player_count < 2 {
-            panic!("Need atleast 2 players to define a RoundState");
-        }
         if player_count > 16 {
             panic!("RoundState has no support for more than 16 players");
         }
+        if config.button >= player_count {
+            panic!("Button seat {} does not exist with {} players", config.button, player_count);
+        }
+        let sitting_out = sitting_out_mask(&config, &free_chips);
+        let active_count = player_count - sitting_out.count_ones() as usize;
+        if active_count < 2 {
+            panic!("Need atleast 2 players to define a RoundState");
+        }
+        if player_cards.len() != player_count {
+            panic!("Expected hole cards for {} players, got {}", player_count, player_cards.len());
+        }
+        for (i, cards) in player_cards.iter_mut().enumerate() {
+            if sitting_out & (1 << i) != 0 {
+                *cards = CardSet::new(&[]);
+            } else if cards.len() != 2 {
+                panic!("Every player needs exactly 2 hole cards");
+            }
+        }
+        if community_cards.len() != 5 {
+            panic!("Expected 5 community cards, got {}", community_cards.len());
+        }
+        let dealt: CardMask = community_cards.iter().chain(player_cards.iter().flat_map(|cards| cards.iter())).copied().collect();
+        if dealt.len() != 5 + 2 * active_count {
+            panic!("The same card can't be dealt more than once");
+        }
 
-        let mut rng = thread_rng();
-        let mut deck: Vec<u8> = (0..52).collect();
-        deck.shuffle(&mut rng);
+        let mut rs = RoundState {
+            player_count,
+            community_cards,
+            player_cards,
+            bet_chips: vec![0; player_count],
+            street_bets: vec![0; player_count],
+            start_chips: free_chips.clone(),
+            free_chips,
+            button: config.button as u8,
+            sitting_out,
+            folded: sitting_out,
+            min_raise: match config.betting {
+                BettingStructure::FixedLimit { small_bet, .. } => small_bet,
+                _ => config.big_blind.max(config.straddle.unwrap_or(0)),
+            },
+            // Room for the hole cards, forced bets and streets plus a few actions per player
+            history: config.record_history.then(|| HandHistory { events: Vec::with_capacity(8 + 6 * player_count) }),
+            config,
+            ..Default::default()
+        };
 
-        let button: usize = 0;
+        let button = rs.config.button;
         let small_blind_index;
         let big_blind_index;
-        let turn;
-        if player_count == 2 {
-            // The normal rules for positioning the blinds do not apply when there are only two players at the table.
+        if active_count == 2 {
             // The player on the button is always due the small blind, and the other player must pay the big blind.
-            // The player on the button is therefore the first to act before the flop, but last to act for all remaining betting rounds.
-            small_blind_index = button;
-            big_blind_index = (button + 1) % player_count;
-            turn = small_blind_index;
+            let small_blind = rs.acting_button();
+            small_blind_index = Some(small_blind);
+            big_blind_index = rs.next_seat_dealt_in(small_blind);
+        } else if rs.config.dead_small_blind {
+            small_blind_index = None;
+            big_blind_index = rs.next_seat_dealt_in(button);
         } else {
-            small_blind_index = (button + 1) % player_count;
-            big_blind_index = (button + 2) % player_count;
-            turn = (button + 3) % player_count;
+            let small_blind = rs.next_seat_dealt_in(button);
+            small_blind_index = Some(small_blind);
+            big_blind_index = rs.next_seat_dealt_in(small_blind);
         }
+        let straddle_index = rs.config.straddle.filter(|_| active_count > 2).map(|_| rs.next_seat_dealt_in(big_blind_index));
 
-        let mut rs = RoundState {
-            player_count,
-            community_cards: deck[0..5].into(),
-            player_cards: (0..player_count).into_iter().map(|i| deck[5+2*i..7+2*i].into()).collect(),
-            bet_chips: vec![0.0; player_count],
-            start_chips: free_chips.clone(),
-            free_chips: free_chips,
-            button: button as u8,
-            turn: turn as u8,
-            ..Default::default()
-        };
-        
-        let small_blind_amount = f32::min(0.5, rs.free_chips[small_blind_index]);
-        let big_blind_amount: f32 = f32::min(1.0, rs.free_chips[big_blind_index]);
-        rs.bet_chips[small_blind_index] = small_blind_amount;
-        rs.free_chips[small_blind_index] -= small_blind_amount;
-        rs.bet_chips[big_blind_index] = big_blind_amount;
-        rs.free_chips[big_blind_index] -= big_blind_amount;
+        for i in 0..player_count {
+            if sitting_out & (1 << i) == 0 {
+                rs.post(i, ForcedBet::Ante, rs.config.ante);
+            }
+        }
+        // A short big blind covers the big blind ante first, the rest goes to the blind
+        rs.post(big_blind_index, ForcedBet::BigBlindAnte, rs.config.big_blind_ante);
+        if let Some(small_blind_index) = small_blind_index {
+            rs.post(small_blind_index, ForcedBet::SmallBlind, rs.config.small_blind);
+        }
+        rs.post(big_blind_index, ForcedBet::BigBlind, rs.config.big_blind);
+        if let (Some(straddle_index), Some(straddle)) = (straddle_index, rs.config.straddle) {
+            rs.post(straddle_index, ForcedBet::Straddle, straddle);
+        }
+        if let Some(history) = rs.history.as_mut() {
+            for (seat, cards) in rs.player_cards.iter().enumerate().filter(|&(i, _)| sitting_out & (1 << i) == 0) {
+                history.push(HandEvent::HoleCards { seat, cards: cards.clone() });
+            }
+        }
 
+        // First round: the player to the left of the blinds (or straddle) begins.
+        let last_forced_bet = straddle_index.unwrap_or(big_blind_index);
+        rs.start_street(rs.next_seat_dealt_in(last_forced_bet));
         rs
     }
 
+    // First seat after 'seat' (clockwise) that is not sitting out
+    fn next_seat_dealt_in(&self, seat: usize) -> usize {
+        let mut next = (seat + 1) % self.player_count;
+        while self.sitting_out & (1 << next) != 0 {
+            next = (next + 1) % self.player_count;
+        }
+        next
+    }
+
+    // The seat that acts last after the flop. Heads-up the small blind has the button,
+    // with the button seat sitting out that is the first player after it.
+    fn acting_button(&self) -> usize {
+        let button = self.button as usize;
+        let active_count = self.player_count - self.sitting_out.count_ones() as usize;
+        if active_count == 2 && self.sitting_out & (1 << button) != 0 {
+            self.next_seat_dealt_in(button)
+        } else {
+            button
+        }
+    }
+
+    // Posts a forced bet, live bets count towards the player's bet on this street
+    fn post(&mut self, seat: usize, forced_bet: ForcedBet, amount: Chips) {
+        let amount = Chips::min(amount, self.free_chips[seat]);
+        if amount == 0 {
+            return;
+        }
+        self.free_chips[seat] -= amount;
+        self.bet_chips[seat] += amount;
+        if forced_bet.is_live() {
+            self.street_bets[seat] += amount;
+        }
+        self.record(HandEvent::Post { seat, forced_bet, amount });
+    }
+
+    fn record(&mut self, event: HandEvent) {
+        if let Some(history) = self.history.as_mut() {
+            history.push(event);
+        }
+    }
+
+    // Community cards that are revealed on the given street
+    pub fn street_cards(&self, stage: RoundStage) -> &[Card] {
+        match stage {
+            RoundStage::PreFlop | RoundStage::Finished => &[],
+            RoundStage::Flop => &self.community_cards.cards[0..3],
+            RoundStage::Turn => &self.community_cards.cards[3..4],
+            RoundStage::River => &self.community_cards.cards[4..5],
+        }
+    }
+
+    fn record_street(&mut self, stage: RoundStage) {
+        if self.history.is_some() {
+            let cards = CardSet::from(self.street_cards(stage));
+            self.record(HandEvent::Street { stage, cards });
+        }
+    }
+
+    // Once finished, only the streets that were played
     pub fn revealed_community_cards(&self) -> &[Card] {
-        match self.stage {
+        let stage = self.showdown.as_ref().map_or(self.stage, |showdown| showdown.final_stage);
+        match stage {
             RoundStage::PreFlop => &self.community_cards.cards[0..0],
             RoundStage::Flop => &self.community_cards.cards[0..3],
             RoundStage::Turn => &self.community_cards.cards[0..4],
@@ -173,120 +369,404 @@ impl RoundState {
         }
     }
 
+    // Highest amount bet by a player on the current street
+    pub fn highest_street_bet(&self) -> Chips {
+        let highest_bet = self.street_bets.iter().copied().max().unwrap_or(0);
+        if self.stage == RoundStage::PreFlop {
+            Chips::max(highest_bet, self.config.big_blind)
+        } else {
+            highest_bet
+        }
+    }
+
+    // Amount the player has bet on the current street
+    pub fn street_bet(&self, seat: usize) -> Chips {
+        self.street_bets[seat]
+    }
+
+    // Main and side pots as they stand, bets of the current street included
+    pub fn pots(&self) -> Pots {
+        let all_in = (0..self.player_count)
+            .filter(|&i| self.free_chips[i] == 0)
+            .fold(0u16, |mask, i| mask | (1 << i));
+        Pots::new(&self.bet_chips, self.folded, all_in)
+    }
+
+    // All chips in play, bet or not. This doesn't change during a round.
+    pub fn total_chips(&self) -> Chips {
+        self.free_chips.iter().sum::<Chips>() + self.bet_chips.iter().sum::<Chips>()
+    }
+
+    // What the player knows: public state, own hole cards and the public history
+    pub fn player_view(&self, seat: usize) -> PlayerView {
+        PlayerView::new(self, seat)
+    }
+
+    // Actions available to the player whose turn it is, None once the round is finished or being run out
+    pub fn legal_actions(&self) -> Option<LegalActions> {
+        if self.is_finished() || self.is_running_out() {
+            return None;
+        }
+        let seat = self.turn as usize;
+        let highest_bet = self.highest_street_bet();
+        let street_bet = self.street_bet(seat);
+        let free_chips = self.free_chips[seat];
+        let call_amount = Chips::min(highest_bet.saturating_sub(street_bet), free_chips);
+        let all_in_to = street_bet + free_chips;
+        let max_raise_to = match self.config.betting {
+            BettingStructure::NoLimit => all_in_to,
+            // Call, then raise by the size of the pot
+            BettingStructure::PotLimit => highest_bet + self.pots().total() + call_amount,
+            BettingStructure::FixedLimit { .. } => highest_bet + self.min_raise,
+        };
+        // Nobody could call a raise when everyone else still in the hand is all-in
+        let others_can_act = (0..self.player_count).any(|i| i != seat && self.can_act(i));
+        let max_raise_to = if others_can_act {
+            Chips::min(max_raise_to, all_in_to)
+        } else {
+            Chips::min(highest_bet, all_in_to)
+        };
+        let capped = match self.config.betting {
+            BettingStructure::FixedLimit { max_bets, .. } => self.bets_on_street() >= max_bets,
+            _ => false,
+        };
+        let may_raise = self.is_reopened(seat) && !capped && others_can_act;
+        Some(LegalActions {
+            seat,
+            can_fold: true,
+            can_check: highest_bet <= street_bet,
+            can_call: highest_bet > street_bet,
+            call_amount,
+            can_bet: highest_bet == 0 && free_chips > 0,
+            can_raise: highest_bet > 0 && free_chips > call_amount && may_raise,
+            can_all_in: free_chips > 0 && (free_chips <= call_amount || (may_raise && all_in_to <= max_raise_to)),
+            min_raise_to: Chips::min(highest_bet + self.min_raise, max_raise_to),
+            max_raise_to,
+        })
+    }
+
+    // Bets and raises made on the current street, pre-flop the big blind counts as the first bet
+    pub fn bets_on_street(&self) -> u8 {
+        let (mut bets, mut highest_bet) = if self.stage == RoundStage::PreFlop { (1, self.config.big_blind) } else { (0, 0) };
+        for &(_, bet) in self.street_actions.iter() {
+            if bet > highest_bet {
+                bets += 1;
+                highest_bet = bet;
+            }
+        }
+        bets
+    }
+
+    // Whether the player may (re-)raise: they haven't acted on this street yet,
+    // or the bet increased by at least a full raise since they did
+    pub fn is_reopened(&self, seat: usize) -> bool {
+        match self.street_actions.iter().rev().find(|&&(acted, _)| acted == seat) {
+            Some(&(_, highest_bet)) => self.highest_street_bet() >= highest_bet + self.min_raise,
+            None => true,
+        }
+    }
+
+    // Lenient variant of try_apply, any bet size (in big blinds) is clipped to a legal action:
     //              Fold:                 bet_size <  0.0
     // Check/Check-raise:          0.0 <= bet_size <= check_amount
     //     Raise, All-in: check_amount <  bet_size
+    // During a step-wise run-out the next street is dealt instead.
     pub fn do_action(&mut self, bet_size: f32) {
-        if bet_size < 0.0 {
-            // Fold
-            self.folded |= 1 << self.turn;
+        if self.advance_run_out().is_some() {
+            return;
+        }
+        let legal = self.legal_actions().expect("Can't act in a finished round");
+        let street_bet = self.street_bet(legal.seat);
+
+        let (action, chips_added) = if bet_size < 0.0 {
+            (Action::Fold, 0)
+        } else if self.config.big_blinds_to_chips(bet_size) <= legal.call_amount || !(legal.can_bet || legal.can_raise) {
+            (if legal.can_call { Action::Call } else { Action::Check }, legal.call_amount)
         } else {
-            let turn_index = self.turn as usize;
-
-            let check_amount = f32::min(
-                self.bet_chips.clone().into_iter().reduce(f32::max).unwrap_or(0.0) - self.bet_chips[turn_index],
-                self.free_chips[turn_index]
-            );
-            let raise_amount: f32;
-            if bet_size <= check_amount {
-                // Check, Check-raise
-                raise_amount = 0.0;
+            let to = (street_bet + self.config.big_blinds_to_chips(bet_size)).clamp(legal.min_raise_to, legal.max_raise_to);
+            if to >= street_bet + self.free_chips[legal.seat] {
+                (Action::AllIn, to - street_bet)
+            } else if legal.can_bet {
+                (Action::Bet(to), to - street_bet)
             } else {
-                // Raise, All-in
-                raise_amount = f32::min(
-                    f32::max(bet_size - check_amount, self.min_raise),
-                    self.free_chips[turn_index] - check_amount
-                );
-                self.min_raise = f32::max(self.min_raise, raise_amount);
+                (Action::Raise(to), to - street_bet)
             }
-            let clipped_bet_size = check_amount + raise_amount;
-            self.bet_chips[turn_index] += clipped_bet_size;
-            self.free_chips[turn_index] -= clipped_bet_size;
-            if raise_amount > 0.0 {
-                self.last_raise_by = self.turn;
+        };
+        self.apply(legal.seat, action, chips_added);
+    }
+
+    // Applies the action for the player in the given seat, illegal actions are rejected without changing the state.
+    pub fn try_apply(&mut self, seat: usize, action: Action) -> Result<ActionOutcome, ActionError> {
+        if self.is_running_out() {
+            return Err(ActionError::BettingClosed);
+        }
+        let legal = self.legal_actions().ok_or(ActionError::RoundFinished)?;
+        if seat != legal.seat {
+            return Err(ActionError::OutOfTurn { seat, turn: legal.seat });
+        }
+        let street_bet = self.street_bet(seat);
+        let available = self.free_chips[seat];
+
+        let chips_added = match action {
+            Action::Fold => 0,
+            Action::Check if !legal.can_check => {
+                return Err(ActionError::CannotCheck { call_amount: legal.call_amount });
+            }
+            Action::Check => 0,
+            Action::Call if !legal.can_call => return Err(ActionError::NothingToCall),
+            Action::Call => legal.call_amount,
+            Action::Bet(_) if !legal.can_bet => return Err(ActionError::CannotBet),
+            Action::Raise(_) if legal.can_bet => return Err(ActionError::CannotRaise),
+            Action::Raise(_) if !legal.can_raise => return Err(ActionError::RaiseNotAllowed),
+            Action::Bet(to) | Action::Raise(to) => {
+                let amount = to.saturating_sub(street_bet);
+                if amount > available {
+                    return Err(ActionError::NotEnoughChips { amount, available });
+                }
+                if to > legal.max_raise_to {
+                    return Err(ActionError::AboveMaximum { amount: to, max: legal.max_raise_to });
+                }
+                if to < legal.min_raise_to {
+                    return Err(ActionError::BelowMinimum { amount: to, min: legal.min_raise_to });
+                }
+                amount
+            }
+            Action::AllIn if !legal.can_all_in => return Err(ActionError::RaiseNotAllowed),
+            Action::AllIn => available,
+        };
+        Ok(self.apply(seat, action, chips_added))
+    }
+
+    // Applies an action that is known to be legal
+    fn apply(&mut self, seat: usize, action: Action, chips_added: Chips) -> ActionOutcome {
+        let stage = self.stage;
+        if action == Action::Fold {
+            self.folded |= 1 << seat;
+            self.record(HandEvent::Action { seat, stage, action, chips_added, pot: self.bet_chips.iter().sum() });
+            if self.players_in_hand() == 1 {
+                // Everyone else folded, the pot is awarded without a showdown
+                self.stage = RoundStage::Finished;
+                self.finish_game(stage);
+                return ActionOutcome { seat, action, chips_added, stage_changed: true, finished: true, run_out: false };
+            }
+        } else {
+            let raise_amount = (self.street_bet(seat) + chips_added).saturating_sub(self.highest_street_bet());
+            self.bet_chips[seat] += chips_added;
+            self.street_bets[seat] += chips_added;
+            self.free_chips[seat] -= chips_added;
+            if raise_amount > 0 {
+                // Incomplete raises still have to be called, but don't change the minimum raise
+                self.min_raise = Chips::max(self.min_raise, raise_amount);
+                self.last_raise_by = seat as u8;
+            }
+            self.street_actions.push((seat, self.highest_street_bet()));
+            self.record(HandEvent::Action { seat, stage, action, chips_added, pot: self.bet_chips.iter().sum() });
+        }
+
+        if self.is_betting_closed() {
+            // Nobody is left to bet against (e.g. after a fold), the street ends right away
+            self.end_street();
+        } else {
+            self.next_turn();
+        }
+        ActionOutcome {
+            seat,
+            action,
+            chips_added,
+            stage_changed: self.stage != stage,
+            finished: self.is_finished(),
+            run_out: self.run_out_from.is_some(),
+        }
+    }
+
+    fn can_act(&self, seat: usize) -> bool {
+        (self.folded & (1 << seat)) == 0 && self.free_chips[seat] > 0
+    }
+
+    // No more betting is possible when at most one player can still act and that player has nothing to call
+    pub fn is_betting_closed(&self) -> bool {
+        if self.is_finished() {
+            return true;
+        }
+        let highest_bet = self.highest_street_bet();
+        let mut acting = (0..self.player_count).filter(|&i| self.can_act(i));
+        match (acting.next(), acting.next()) {
+            (None, _) => true,
+            (Some(seat), None) => self.street_bets[seat] >= highest_bet,
+            _ => false,
+        }
+    }
+
+    // Betting is closed and the remaining streets are still to be dealt, see advance_run_out
+    pub fn is_running_out(&self) -> bool {
+        self.run_out_from.is_some() && !self.is_finished()
+    }
+
+    // Deals the next street of an all-in run-out and returns it, the round is finished once the river is dealt.
+    // Only needed with TableConfig::step_run_out, otherwise all streets are dealt when betting closes.
+    pub fn advance_run_out(&mut self) -> Option<RoundStage> {
+        if !self.is_running_out() {
+            return None;
+        }
+        self.stage = self.stage.next();
+        self.record_street(self.stage);
+        let dealt = self.stage;
+        if dealt == RoundStage::River {
+            self.stage = RoundStage::Finished;
+            self.finish_game(RoundStage::River);
+        }
+        Some(dealt)
+    }
+
+    // Streets that were dealt without any betting (all-in run-out) with the cards revealed on each,
+    // during a step-wise run-out only the streets dealt so far
+    pub fn run_out_streets(&self) -> Vec<(RoundStage, &[Card])> {
+        let Some(from) = self.run_out_from else {
+            return vec![];
+        };
+        [RoundStage::Flop, RoundStage::Turn, RoundStage::River]
+            .into_iter()
+            .filter(|&stage| stage as u8 >= from as u8 && stage as u8 <= self.stage as u8)
+            .map(|stage| (stage, self.street_cards(stage)))
+            .collect()
+    }
+
+    // The street ends once the turn comes back around to 'first_to_act' without anyone raising
+    fn start_street(&mut self, first_to_act: usize) {
+        if self.is_betting_closed() {
+            // Everyone is all-in (or called the only player with chips left): deal the remaining streets
+            self.run_out_from = Some(match self.stage {
+                RoundStage::PreFlop => RoundStage::Flop,
+                stage => stage,
+            });
+            self.street_bets.fill(0);
+            self.street_actions.clear();
+            if self.stage == RoundStage::River {
+                self.stage = RoundStage::Finished;
+                self.finish_game(RoundStage::River);
+            } else if !self.config.step_run_out {
+                while self.advance_run_out().is_some() {}
             }
+            return;
         }
-        
-        // Change state, turn (& last_raise_by)
+        self.turn = first_to_act as u8;
+        self.last_raise_by = self.turn;
+        if !self.can_act(first_to_act) {
+            self.next_turn();
+        }
+    }
+
+    // Change state, turn (& last_raise_by)
+    fn next_turn(&mut self) {
         loop {
             self.turn = (self.turn + 1) % (self.player_count as u8);
             if self.turn == self.last_raise_by {
                 // Went full circle without anyone raising, go to next stage.
-                self.stage = self.stage.next();
-                if self.is_finished() {
-                    self.finish_game();
-                    break;
-                } else {
-                    self.turn = (self.button + 1) % (self.player_count as u8);
-                    self.last_raise_by = self.turn;
-                }
+                self.end_street();
+                return;
             }
-            if (self.folded & (1 << self.turn)) == 0 && self.free_chips[self.turn as usize] > 0.0 {
+            if self.can_act(self.turn as usize) {
                 // Found next player who can act
-                break;
+                return;
             }
         }
     }
 
+    fn end_street(&mut self) {
+        let stage = self.stage;
+        self.stage = self.stage.next();
+        self.street_bets.fill(0);
+        self.street_actions.clear();
+        self.min_raise = self.config.min_bet(self.stage);
+        if self.is_finished() {
+            self.finish_game(stage);
+        } else {
+            self.record_street(self.stage);
+            self.start_street(self.next_seat_dealt_in(self.acting_button()));
+        }
+    }
+
     pub fn is_finished(&self) -> bool {
-        return self.stage == RoundStage::Finished;
+        self.stage == RoundStage::Finished
+    }
+
+    // Players that haven't folded (or are sitting out), all-in players included
+    pub fn players_in_hand(&self) -> usize {
+        (0..self.player_count).filter(|&i| self.folded & (1 << i) == 0).count()
     }
 
-    fn finish_game(&mut self) {       
-        let mut winner_order: Vec<(u8, f32, i32)> = Vec::new(); // (player_idx, bet_chips, hand_strength)
+    // 'final_stage' is the last street that was played
+    fn finish_game(&mut self, final_stage: RoundStage) {
+        let total_chips = self.total_chips();
+        let free_chips_before = self.free_chips.clone();
+        let contributions = self.bet_chips.clone();
+
+        // The part of the biggest bet no one else matched (e.g. an uncalled raise) is returned
+        let mut uncalled_bet = None;
+        let mut by_contribution: Vec<usize> = (0..self.player_count).collect();
+        by_contribution.sort_by_key(|&i| std::cmp::Reverse(self.bet_chips[i]));
+        if let [first, second, ..] = by_contribution[..] {
+            let excess = self.bet_chips[first] - self.bet_chips[second];
+            if excess > 0 {
+                self.bet_chips[first] -= excess;
+                self.free_chips[first] += excess;
+                uncalled_bet = Some((first, excess));
+            }
+        }
+
+        // Without a showdown no cards are shown, the last player left wins every pot
+        let uncontested = self.players_in_hand() == 1;
+        let mut hands = Vec::new();
+        let mut contenders = Vec::new(); // (seat, hand_rank)
         for i in 0..self.player_count {
-            if self.folded & (1 << i) == 1 {
+            if self.folded & (1 << i) != 0 {
                 continue;
             }
-            let mut card_set = self.community_cards.clone();
-            card_set.set_cards_partial(&self.player_cards[i].cards[0..2], 5);
-            card_set.canonicalize();
-            winner_order.push((i as u8, self.bet_chips[i], card_set.evaluate()));
-        }
-        
-        // winner_order is sorted to have lowest bet size first for equal strength hands
-        winner_order.sort_by(|(_, a_bet, _), (_, b_bet, _)| a_bet.partial_cmp(b_bet).unwrap_or(Equal));
-        winner_order.sort_by(|&(_, _, a_val), &(_, _, b_val)| b_val.cmp(&a_val));
-
-        for i in 0..winner_order.len() {
-            let (fw_index, _, fw_val) = winner_order[i];
-            let pot_contribution: f32 = self.bet_chips[fw_index as usize];
-            if pot_contribution <= 0.0 {
+            if uncontested {
+                contenders.push((i, HandRank(0)));
                 continue;
             }
+            let mut cards = self.player_cards[i].clone();
+            cards.set_cards_partial(self.revealed_community_cards(), 2);
+            let hand_rank = HandRank(lookup_eval::evaluate(&cards));
+            hands.push(ShownHand { seat: i, cards, hand_rank });
+            contenders.push((i, hand_rank));
+        }
 
-            // Find between how many winners this pot is split
-            let mut pot_winners = 1;
-            for j in i+1..winner_order.len() {
-                let (_, _, p_val) = winner_order[j];
-                if fw_val != p_val {
-                    break;
-                }
-                pot_winners += 1;
-            }
-
-            let mut pot = 0.0f32;
-            let mut chips_left = false;
-            // fill pot
-            for j in 0..self.player_count {
-                let bet_amount = f32::min(self.bet_chips[j], pot_contribution);
-                self.bet_chips[j] -= bet_amount;
-                if self.bet_chips[j] != 0.0 {
-                    chips_left = true;
-                }
-                pot += bet_amount;
-            }
-
-            // distribute pot
-            let winnings_per_winner = pot / (pot_winners as f32);
-            for j in i..i+pot_winners {
-                self.free_chips[winner_order[j].0 as usize] += winnings_per_winner;
-            }
+        let mut pots = Vec::new();
+        for pot in self.pots().iter() {
+            let best_rank = contenders.iter()
+                .filter(|&&(seat, _)| pot.is_eligible(seat))
+                .map(|&(_, hand_rank)| hand_rank)
+                .max();
+            let mut winners: Vec<usize> = contenders.iter()
+                .filter(|&&(seat, hand_rank)| pot.is_eligible(seat) && Some(hand_rank) == best_rank)
+                .map(|&(seat, _)| seat)
+                .collect();
+            // A pot is capped by what a player still in the hand put in and that player is eligible for it.
+            // Only a pot of folded chips without any contribution of the players left has no one eligible,
+            // which can't happen: the last players in the hand at least called the blinds or posted an ante.
+            assert!(!winners.is_empty(), "Nobody is eligible for the pot of {}", pot.amount);
 
-            // TODO: needed? we already select the winners pretty aggressively
-            if !chips_left {
-                break;
+            // The odd chips go to the winners closest to the left of the button
+            winners.sort_by_key(|&index| (index + self.player_count - self.button as usize - 1) % self.player_count);
+            let winnings_per_winner = pot.amount / (winners.len() as Chips);
+            let mut odd_chips = pot.amount % (winners.len() as Chips);
+            let mut winnings = Vec::with_capacity(winners.len());
+            for &winner_index in winners.iter() {
+                let odd_chip = Chips::from(odd_chips > 0);
+                odd_chips -= odd_chip;
+                self.free_chips[winner_index] += winnings_per_winner + odd_chip;
+                winnings.push(winnings_per_winner + odd_chip);
             }
+            pots.push(PotResult { amount: pot.amount, eligible: pot.eligible, winners, winnings });
         }
+        self.bet_chips.fill(0);
+        assert_eq!(total_chips, self.total_chips(), "Chips must be conserved when distributing the pot");
+
+        let net_winnings = (0..self.player_count)
+            .map(|i| self.free_chips[i] as i64 - free_chips_before[i] as i64 - contributions[i] as i64)
+            .collect();
+        self.showdown = Some(ShowdownResult { final_stage, uncontested, hands, pots, uncalled_bet, net_winnings });
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,46 @@
+use crate::card_set::CardSet;
+use crate::hand_rank::HandRank;
+use crate::round_state::{Chips, RoundStage};
+
+
+// A hand that went to showdown
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShownHand {
+    pub seat: usize,
+    pub cards: CardSet, // Hole cards followed by the community cards
+    pub hand_rank: HandRank,
+}
+
+impl ShownHand {
+    // The 5 cards that make up the hand, in the original suits.
+    // Not stored as finding them takes 21 evaluations.
+    pub fn best_five(&self) -> CardSet {
+        self.cards.best_five().cards
+    }
+}
+
+// Main pot (first) or side pot, with the players that could win it
+#[derive(Clone, Debug, PartialEq)]
+pub struct PotResult {
+    pub amount: Chips,
+    pub eligible: u16, // Bitmask of the players that were in contention for this pot
+    pub winners: Vec<usize>,
+    pub winnings: Vec<Chips>, // Chips won by each winner, the same order as 'winners'
+}
+
+// How the pot was awarded, with or without a showdown
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShowdownResult {
+    pub final_stage: RoundStage, // Last street that was played
+    pub uncontested: bool, // Everyone but one player folded, no cards were shown
+    pub hands: Vec<ShownHand>, // Empty when uncontested
+    pub pots: Vec<PotResult>,
+    pub uncalled_bet: Option<(usize, Chips)>, // (seat, chips) returned before the pots were built
+    pub net_winnings: Vec<i64>, // Per player: chips won minus chips put in the pot this round
+}
+
+impl ShowdownResult {
+    pub fn hand(&self, seat: usize) -> Option<&ShownHand> {
+        self.hands.iter().find(|hand| hand.seat == seat)
+    }
+}
@@ -0,0 +1,246 @@
+use std::error::Error;
+use std::fmt;
+use crate::card::Deck;
+use crate::round_state::{Chips, RoundState};
+use crate::table_config::TableConfig;
+
+
+// --- DEAD BUTTON ---
+// The big blind moves to the next player who is dealt in every hand, so nobody skips it when players leave.
+// The previous big blind posts the small blind, if that player left (or busted) the small blind is dead.
+// The button moves to the previous small blind's seat, even when that seat is now empty.
+// Players that sit down between the button and the blinds wait (aren't dealt in) until the button passed them.
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Seat {
+    pub stack: Chips,
+    pub sitting_out: bool, // Not dealt in, stays seated
+    pub net_result: i64, // Chips won minus chips lost over all hands played at this table
+    pub hands_played: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TableError {
+    NoSuchSeat { seat: usize },
+    SeatTaken { seat: usize },
+    SeatEmpty { seat: usize },
+    NoChips,
+    HandInProgress,
+    NoHandInProgress,
+    HandNotFinished,
+    NotEnoughPlayers,
+}
+
+impl fmt::Display for TableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TableError::NoSuchSeat { seat } => write!(f, "seat {} does not exist", seat),
+            TableError::SeatTaken { seat } => write!(f, "seat {} is already taken", seat),
+            TableError::SeatEmpty { seat } => write!(f, "seat {} is empty", seat),
+            TableError::NoChips => write!(f, "a player needs chips to sit down"),
+            TableError::HandInProgress => write!(f, "not possible while the player is in a hand"),
+            TableError::NoHandInProgress => write!(f, "there is no hand in progress"),
+            TableError::HandNotFinished => write!(f, "the hand is not finished yet"),
+            TableError::NotEnoughPlayers => write!(f, "need atleast 2 players to start a hand"),
+        }
+    }
+}
+
+impl Error for TableError {}
+
+// Seats and stacks of a table over successive hands, seat indices are the player indices of the RoundStates
+pub struct Table {
+    pub config: TableConfig, // Blinds and betting structure, the button and sitting out seats are set by the table
+    pub seats: Vec<Option<Seat>>,
+    pub button: usize,
+    pub hands_played: u32,
+    pub round: Option<RoundState>, // Hand in progress
+    pub last_round: Option<RoundState>,
+    small_blind: Option<usize>, // Small blind position of the previous hand, the seat may be empty
+    big_blind: Option<usize>, // Big blind of the previous hand
+    deck: Deck,
+}
+
+impl Table {
+    // Shuffles with the thread rng
+    pub fn new(config: TableConfig, seat_count: usize) -> Self {
+        Table::with_deck(config, seat_count, Deck::new())
+    }
+
+    pub fn from_seed(config: TableConfig, seat_count: usize, seed: u64) -> Self {
+        Table::with_deck(config, seat_count, Deck::from_seed(seed))
+    }
+
+    // The first hand uses the button of the config
+    pub fn with_deck(config: TableConfig, seat_count: usize, deck: Deck) -> Self {
+        if !(2..=16).contains(&seat_count) {
+            panic!("A table has 2 to 16 seats, got {}", seat_count);
+        }
+        if config.button >= seat_count {
+            panic!("Button seat {} does not exist with {} seats", config.button, seat_count);
+        }
+        Table {
+            button: config.button,
+            config,
+            seats: vec![None; seat_count],
+            hands_played: 0,
+            round: None,
+            last_round: None,
+            small_blind: None,
+            big_blind: None,
+            deck,
+        }
+    }
+
+    pub fn seat(&self, seat: usize) -> Option<&Seat> {
+        self.seats.get(seat).and_then(|seat| seat.as_ref())
+    }
+
+    // Players can join during a hand, they are dealt in from the next hand on
+    pub fn join(&mut self, seat: usize, stack: Chips) -> Result<(), TableError> {
+        match self.seats.get(seat) {
+            None => Err(TableError::NoSuchSeat { seat }),
+            Some(Some(_)) => Err(TableError::SeatTaken { seat }),
+            Some(None) if stack == 0 => Err(TableError::NoChips),
+            Some(None) => {
+                self.seats[seat] = Some(Seat { stack, sitting_out: false, net_result: 0, hands_played: 0 });
+                Ok(())
+            }
+        }
+    }
+
+    // Returns the seat with the player's stack and results, not possible while the player is in a hand
+    pub fn leave(&mut self, seat: usize) -> Result<Seat, TableError> {
+        self.seat_or_error(seat)?;
+        if self.is_in_hand(seat) {
+            return Err(TableError::HandInProgress);
+        }
+        Ok(self.seats[seat].take().unwrap())
+    }
+
+    // Takes effect from the next hand on
+    pub fn set_sitting_out(&mut self, seat: usize, sitting_out: bool) -> Result<(), TableError> {
+        self.seat_or_error(seat)?;
+        self.seats[seat].as_mut().unwrap().sitting_out = sitting_out;
+        Ok(())
+    }
+
+    // Dealt in the hand that is in progress
+    pub fn is_in_hand(&self, seat: usize) -> bool {
+        self.round.as_ref().is_some_and(|round| round.sitting_out & (1 << seat) == 0)
+    }
+
+    pub fn start_hand(&mut self) -> Result<&mut RoundState, TableError> {
+        if self.round.is_some() {
+            return Err(TableError::HandInProgress);
+        }
+        let mut dealt_in = (0..self.seats.len())
+            .filter(|&i| self.seat(i).is_some_and(|seat| !seat.sitting_out && seat.stack > 0))
+            .fold(0u16, |mask, i| mask | (1 << i));
+        if dealt_in.count_ones() < 2 {
+            return Err(TableError::NotEnoughPlayers);
+        }
+
+        let (button, small_blind, big_blind) = match self.big_blind {
+            None => self.positions_from(self.button, dealt_in),
+            Some(_) if dealt_in.count_ones() == 2 => self.heads_up_positions(dealt_in),
+            Some(previous_big_blind) => {
+                let button = self.small_blind.unwrap_or(self.button);
+                let small_blind = previous_big_blind;
+                let big_blind = next_seat(dealt_in, previous_big_blind, self.seats.len());
+                // Nobody can be dealt in between the button and the blinds
+                let first_blind = if dealt_in & (1 << small_blind) != 0 { small_blind } else { big_blind };
+                let mut waiting = 0u16;
+                let mut seat = (button + 1) % self.seats.len();
+                while seat != first_blind {
+                    waiting |= 1 << seat;
+                    seat = (seat + 1) % self.seats.len();
+                }
+                match (dealt_in & !waiting).count_ones() {
+                    0 | 1 => self.positions_from(button, dealt_in),
+                    2 => {
+                        dealt_in &= !waiting;
+                        self.heads_up_positions(dealt_in)
+                    }
+                    _ => {
+                        dealt_in &= !waiting;
+                        (button, small_blind, big_blind)
+                    }
+                }
+            }
+        };
+        self.button = button;
+        self.small_blind = Some(small_blind);
+        self.big_blind = Some(big_blind);
+
+        let config = TableConfig {
+            button,
+            sitting_out: !dealt_in & (((1u32 << self.seats.len()) - 1) as u16),
+            dead_small_blind: dealt_in & (1 << small_blind) == 0,
+            ..self.config.clone()
+        };
+        let stacks = (0..self.seats.len()).map(|i| self.seat(i).map_or(0, |seat| seat.stack)).collect();
+        self.deck.reset();
+        Ok(self.round.insert(RoundState::with_config(config, stacks, &mut self.deck)))
+    }
+
+    // Applies the results of the finished hand to the seats.
+    // Busted players are removed from the table and returned.
+    pub fn finish_hand(&mut self) -> Result<Vec<(usize, Seat)>, TableError> {
+        let round = self.round.take().ok_or(TableError::NoHandInProgress)?;
+        if !round.is_finished() {
+            self.round = Some(round);
+            return Err(TableError::HandNotFinished);
+        }
+        let showdown = round.showdown.as_ref().unwrap();
+        let mut busted = Vec::new();
+        for i in 0..self.seats.len() {
+            if round.sitting_out & (1 << i) != 0 {
+                continue;
+            }
+            let seat = self.seats[i].as_mut().unwrap();
+            seat.stack = round.free_chips[i];
+            seat.net_result += showdown.net_winnings[i];
+            seat.hands_played += 1;
+            if seat.stack == 0 {
+                busted.push((i, self.seats[i].take().unwrap()));
+            }
+        }
+        self.hands_played += 1;
+        self.last_round = Some(round);
+        Ok(busted)
+    }
+
+    fn seat_or_error(&self, seat: usize) -> Result<&Seat, TableError> {
+        match self.seats.get(seat) {
+            None => Err(TableError::NoSuchSeat { seat }),
+            Some(None) => Err(TableError::SeatEmpty { seat }),
+            Some(Some(s)) => Ok(s),
+        }
+    }
+
+    // (button, small blind, big blind) as RoundState places them around the given button
+    fn positions_from(&self, button: usize, dealt_in: u16) -> (usize, usize, usize) {
+        if dealt_in.count_ones() == 2 && dealt_in & (1 << button) != 0 {
+            return (button, button, next_seat(dealt_in, button, self.seats.len()));
+        }
+        let small_blind = next_seat(dealt_in, button, self.seats.len());
+        (button, small_blind, next_seat(dealt_in, small_blind, self.seats.len()))
+    }
+
+    // The big blind moves on, the other player has the button and posts the small blind
+    fn heads_up_positions(&self, dealt_in: u16) -> (usize, usize, usize) {
+        let big_blind = next_seat(dealt_in, self.big_blind.unwrap(), self.seats.len());
+        let button = next_seat(dealt_in, big_blind, self.seats.len());
+        (button, button, big_blind)
+    }
+}
+
+// First seat after 'seat' (clockwise) in the mask
+fn next_seat(mask: u16, seat: usize, seat_count: usize) -> usize {
+    let mut next = (seat + 1) % seat_count;
+    while mask & (1 << next) == 0 {
+        next = (next + 1) % seat_count;
+    }
+    next
+}
@@ -0,0 +1,70 @@
+use crate::round_state::{Chips, RoundStage};
+
+
+pub const SMALL_BLIND: Chips = 50;
+pub const BIG_BLIND: Chips = 100;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BettingStructure {
+    NoLimit,
+    PotLimit, // Raises are capped at the size of the pot after calling
+    FixedLimit {
+        small_bet: Chips, // Bet and raise size pre-flop and on the flop
+        big_bet: Chips, // Bet and raise size on the turn and river
+        max_bets: u8, // Bets and raises allowed per street, the big blind counts as the first bet
+    },
+}
+
+// Forced bets and seating of a table, all amounts are in chips.
+// Antes are dead money: they go into the pot but don't count towards the bet a player has to call.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TableConfig {
+    pub small_blind: Chips,
+    pub big_blind: Chips,
+    pub ante: Chips, // Posted by every player
+    pub big_blind_ante: Chips, // Posted by the big blind on behalf of the table
+    pub straddle: Option<Chips>, // Live blind posted by the player to the left of the big blind (3+ players)
+    pub button: usize, // Seat of the dealer button, may be a seat that is sitting out (dead button)
+    pub sitting_out: u16, // Bitmask of seats that are not dealt in, seats without chips are always skipped
+    pub dead_small_blind: bool, // No small blind this hand (dead button rule), the first player after the button posts the big blind
+    pub betting: BettingStructure,
+    pub record_history: bool, // Keep a HandHistory of every round
+    pub step_run_out: bool, // Deal an all-in run-out one street per RoundState::advance_run_out call instead of all at once
+}
+
+impl Default for TableConfig {
+    fn default() -> TableConfig {
+        TableConfig {
+            small_blind: SMALL_BLIND,
+            big_blind: BIG_BLIND,
+            ante: 0,
+            big_blind_ante: 0,
+            straddle: None,
+            button: 0,
+            sitting_out: 0,
+            dead_small_blind: false,
+            betting: BettingStructure::NoLimit,
+            record_history: true,
+            step_run_out: false,
+        }
+    }
+}
+
+impl TableConfig {
+    pub fn big_blinds_to_chips(&self, big_blinds: f32) -> Chips {
+        (big_blinds * self.big_blind as f32).round().max(0.0) as Chips
+    }
+
+    // Smallest bet on a street, in fixed-limit this is also the raise size
+    pub fn min_bet(&self, stage: RoundStage) -> Chips {
+        match (self.betting, stage) {
+            (BettingStructure::FixedLimit { big_bet, .. }, RoundStage::Turn | RoundStage::River) => big_bet,
+            (BettingStructure::FixedLimit { small_bet, .. }, _) => small_bet,
+            _ => self.big_blind,
+        }
+    }
+
+    pub fn chips_to_big_blinds(&self, chips: Chips) -> f32 {
+        chips as f32 / self.big_blind as f32
+    }
+}
@@ -0,0 +1,47 @@
+#[cfg(test)]
+mod card_mask_test {
+    use poker::card::Card;
+    use poker::card_mask::CardMask;
+    use poker::card_set::CardSet;
+
+    #[test]
+    fn test_set_algebra() {
+        let a: CardMask = "As Ks Qs 2c".into();
+        let b: CardMask = "Ks Qs Jh".into();
+
+        assert_eq!(a | b, "As Ks Qs Jh 2c".into());
+        assert_eq!(a & b, "Ks Qs".into());
+        assert_eq!(a - b, "As 2c".into());
+        assert_eq!((!a).len(), 48);
+        assert_eq!(!CardMask::EMPTY, CardMask::FULL);
+        assert!(CardMask::from("Ks").is_subset(a));
+        assert!((a - b).is_disjoint(b));
+
+        let mut c = CardMask::new();
+        assert!(c.is_empty());
+        assert!(c.insert("Ah".into()));
+        assert!(!c.insert("Ah".into()));
+        assert!(c.contains("Ah".into()));
+        assert!(c.remove("Ah".into()));
+        assert!(!c.remove("Ah".into()));
+        assert!(c.is_empty());
+    }
+
+    #[test]
+    fn test_iteration_and_conversion() {
+        let mask: CardMask = "As 2c Kd".into();
+        assert_eq!(mask.len(), 3);
+        assert_eq!(mask.iter().collect::<Vec<Card>>(), vec!["2c".into(), "Kd".into(), "As".into()]);
+        assert_eq!(CardMask::FULL.iter().count(), 52);
+        assert_eq!(format!("{}", mask), "2c Kd As");
+
+        let card_set: CardSet = mask.into();
+        assert_eq!(card_set, "2c Kd As".into());
+        assert_eq!(CardMask::from(&card_set), mask);
+        assert_eq!(CardMask::from(Card(0)), CardMask(1));
+        assert_eq!(mask.suit_ranks(3), 1 << 12);
+
+        assert!("As As".parse::<CardMask>().is_err());
+        assert_eq!(CardMask::FULL.to_string().parse::<CardMask>(), Ok(CardMask::FULL));
+    }
+}
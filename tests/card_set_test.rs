@@ -3,7 +3,10 @@
 mod cardset_test {
     use std::{collections::HashSet, fs::File, io::{BufReader, BufRead}, path::Path};
 
+    use poker::card::{Card, Deck};
+    use poker::card_mask::CardMask;
     use poker::card_set::CardSet;
+    use poker::lookup_eval;
 
 	fn increment_cardset(card_set: &mut CardSet) -> bool {
 		let card_count = card_set.len();
@@ -23,11 +26,22 @@ mod cardset_test {
             }
             break;
         }
-		return true;
+		true
 	}
 
 	const EXPECTED_HAND_COUNT: [u64; 8] = [0, 52, 1326, 22100, 270725, 2598960, 20358520, 133784560];
 	const EXPECTED_ISOMORPHIC_HAND_COUNT: [u32; 8] = [0, 13, 169, 1755, 16432, 134459, 962988, 6009159];
+	const EXPECTED_EVAL_COUNTS: [(&str, u32); 9] = [
+		("High card",       23294460),
+		("One pair",        58627800),
+		("Two pair",        31433400),
+		("Three of a kind",  6461620),
+		("Straight",         6180020),
+		("Flush",            4047644),
+		("Full house",       3473184),
+		("Four of a kind",    224848),
+		("St/Ro flush",        41584),
+	];
 
 	#[test]
 	fn test_count_different_isomorphic_hands() {
@@ -60,18 +74,6 @@ mod cardset_test {
 
 	#[test]
 	fn test_canonical_eval() {
-		const EXPECTED_EVAL_COUNTS: [(&str, u32); 9] = [
-			("High card",       23294460),
-			("One pair",        58627800),
-			("Two pair",        31433400),
-			("Three of a kind",  6461620),
-			("Straight",         6180020),
-			("Flush",            4047644),
-			("Full house",       3473184),
-			("Four of a kind",    224848),
-			("St/Ro flush",        41584),
-		];
-
 		let mut card_set: CardSet = (0..7u8).collect::<Vec<u8>>().into();
 		let mut eval_type_count = [0u32; 9];
 		let mut hand_count = 0u64;
@@ -109,6 +111,113 @@ mod cardset_test {
 		);
 	}
 
+	#[test]
+	fn test_canonical_eval_5_cards() {
+		// Standard 5 card poker frequencies, 7462 distinct hands
+		const EXPECTED_EVAL_COUNTS: [u32; 9] = [1302540, 1098240, 123552, 54912, 10200, 5108, 3744, 624, 40];
+
+		let mut card_set: CardSet = (0..5u8).collect::<Vec<u8>>().into();
+		let mut eval_type_count = [0u32; 9];
+		let mut evals = HashSet::new();
+		loop {
+			let eval = card_set.clone().as_canonical().evaluate();
+			eval_type_count[(eval >> 20) as usize] += 1;
+			evals.insert(eval);
+			if !increment_cardset(&mut card_set) {
+				break;
+			}
+		}
+		assert_eq!(eval_type_count, EXPECTED_EVAL_COUNTS);
+		assert_eq!(evals.len(), 7462);
+	}
+
+	#[test]
+	fn test_eval_best_five_of_six_and_seven() {
+		let mut deck = Deck::from_seed(5);
+		for card_count in [6, 7] {
+			for _ in 0..20000 {
+				deck.reset();
+				let cards = deck.deal(card_count);
+				// Every 5 card subset, as a mask of the cards to skip
+				let best_of_five = (0u32..1 << card_count)
+					.filter(|skip| card_count - skip.count_ones() as usize == 5)
+					.map(|skip| {
+						let five: Vec<Card> = cards.iter().enumerate()
+							.filter(|&(i, _)| skip & (1 << i) == 0)
+							.map(|(_, &card)| card)
+							.collect();
+						CardSet::from(five).as_canonical().evaluate()
+					})
+					.max()
+					.unwrap();
+				let card_set = CardSet::from(cards);
+				assert_eq!(card_set.clone().as_canonical().evaluate(), best_of_five, "{}", card_set);
+			}
+		}
+	}
+
+	#[test]
+	fn test_best_five() {
+		for (cards, best, kickers) in [
+			("Kh 5d Ac 5s 9h Kd 2c", "Kh Kd 5s 5d Ac", "Ac"),
+			("2h 9h Jh 3c Ah 5h Qh", "Ah Qh Jh 9h 5h", ""),
+			("Ad 2c 3h 4c 5s Kd Kc", "5s 4c 3h 2c Ad", ""),
+			("7h 7d 7s Qc Qs 5d 2c", "7s 7h 7d Qs Qc", ""),
+			("As Kd 9h 7c 4s 3d 2c", "As Kd 9h 7c 4s", "Kd 9h 7c 4s"),
+			("6h 6d 6s 9c As", "6s 6h 6d As 9c", "As 9c"),
+		] {
+			let card_set: CardSet = cards.into();
+			let best_five = card_set.best_five();
+			assert_eq!(best_five.cards, best.into(), "{}", cards);
+			assert_eq!(best_five.kickers, kickers.into(), "{}", cards);
+			assert_eq!(best_five.hand_rank.0, card_set.as_canonical().evaluate());
+		}
+	}
+
+	#[test]
+	fn test_evaluate_any() {
+		let card_set: CardSet = "Kh 5d Ac 5s 9h Kd 2c".into();
+		assert!(!card_set.is_canonical());
+		let canonical = card_set.clone().as_canonical();
+		assert!(canonical.is_canonical());
+		// Order and suits don't matter
+		for cards in ["Kh 5d Ac 5s 9h Kd 2c", "2c Kd 9h 5s Ac 5d Kh", "Ks 5h Ad 5c 9s Kh 2d"] {
+			assert_eq!(CardSet::from(cards).evaluate_any(), canonical.evaluate());
+		}
+	}
+
+	#[test]
+	#[cfg(debug_assertions)]
+	#[should_panic(expected = "use evaluate_any()")]
+	fn test_evaluate_non_canonical() {
+		CardSet::from("Kh 5d Ac 5s 9h Kd 2c").evaluate();
+	}
+
+	#[test]
+	fn test_lookup_eval() {
+		let tables = lookup_eval::tables();
+		let mut card_set: CardSet = (0..7u8).collect::<Vec<u8>>().into();
+		let mut eval_type_count = [0u32; 9];
+		loop {
+			eval_type_count[(tables.evaluate(&card_set) >> 20) as usize] += 1;
+			if !increment_cardset(&mut card_set) {
+				break;
+			}
+		}
+		assert_eq!(eval_type_count, EXPECTED_EVAL_COUNTS.map(|(_, count)| count));
+
+		let mut deck = Deck::from_seed(6);
+		for card_count in [5, 6, 7] {
+			for _ in 0..20000 {
+				deck.reset();
+				let card_set = CardSet::from(deck.deal(card_count));
+				let eval = card_set.evaluate_any();
+				assert_eq!(tables.evaluate(&card_set), eval, "{}", card_set);
+				assert_eq!(lookup_eval::evaluate_mask(CardMask::from(&card_set)), eval, "{}", card_set);
+			}
+		}
+	}
+
 	#[test]
 	fn test_eval_order() {
 		let path = Path::new("./test_resources/eval_order_test.txt");
@@ -123,18 +232,19 @@ mod cardset_test {
 		let mut prev_eval = -1;
 		for (i, line) in reader.lines().enumerate() {
             let curr_str = line.unwrap();
-			if curr_str.len() == 0 {
+			if curr_str.is_empty() {
 				continue;
 			}
-			if curr_str.chars().nth(0).unwrap() == '#' {
+			if curr_str.starts_with('#') {
 				section = curr_str[2..].to_owned();
 				continue;
 			}
 			let cards: CardSet = curr_str[2..].into();
+			assert_eq!(lookup_eval::evaluate(&cards), cards.evaluate_any(), "Lookup table eval differs for '{}'", curr_str);
 			let curr_eval = cards.as_canonical().evaluate();
 
-			if prev_str != "" {
-				let success = match curr_str.chars().nth(0).unwrap() {
+			if !prev_str.is_empty() {
+				let success = match curr_str.chars().next().unwrap() {
 					'>' => prev_eval > curr_eval,
 					'=' => prev_eval == curr_eval,
 					'<' => prev_eval < curr_eval,
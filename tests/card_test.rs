@@ -0,0 +1,94 @@
+#[cfg(test)]
+mod card_test {
+    use poker::card::{Card, Deck, ParseCardError};
+    use poker::card_mask::CardMask;
+    use poker::card_set::CardSet;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    #[test]
+    fn test_parse_card() {
+        assert_eq!("As".parse::<Card>(), Ok(Card(51)));
+        assert_eq!("as".parse::<Card>(), Ok(Card(51)));
+        assert_eq!("2c".parse::<Card>(), Ok(Card(0)));
+        assert_eq!("10h".parse::<Card>(), "Th".parse::<Card>());
+        assert_eq!("tH".parse::<Card>(), "Th".parse::<Card>());
+
+        assert_eq!(
+            "Xs".parse::<Card>(),
+            Err(ParseCardError::InvalidRank { token: "Xs".to_owned(), position: 0 })
+        );
+        assert_eq!(
+            "Ax".parse::<Card>(),
+            Err(ParseCardError::InvalidSuit { token: "Ax".to_owned(), position: 0 })
+        );
+        assert_eq!(
+            "Ash".parse::<Card>(),
+            Err(ParseCardError::UnexpectedCharacter { token: "h".to_owned(), position: 2 })
+        );
+        assert_eq!("".parse::<Card>(), Err(ParseCardError::Empty));
+    }
+
+    #[test]
+    fn test_parse_card_set() {
+        let expected: CardSet = "As Ks 5h 6c 10c".into();
+        for s in ["As Ks 5h 6c Tc", "As,Ks,5h,6c,Tc", "AsKs5h6cTc", "as, ks  5H 6c 10c"] {
+            assert_eq!(s.parse::<CardSet>(), Ok(expected.clone()), "Failed to parse '{}'", s);
+        }
+        assert_eq!("".parse::<CardSet>().map(|cs| cs.len()), Ok(0));
+
+        assert_eq!(
+            "As Ks 5x".parse::<CardSet>(),
+            Err(ParseCardError::InvalidSuit { token: "5x".to_owned(), position: 6 })
+        );
+        assert_eq!(
+            "As*Ks".parse::<CardSet>(),
+            Err(ParseCardError::InvalidRank { token: "*K".to_owned(), position: 2 })
+        );
+        assert_eq!(
+            "As Ks As".parse::<CardSet>(),
+            Err(ParseCardError::DuplicateCard { card: Card(51), position: 6 })
+        );
+        assert_eq!(
+            "2c 3c 4c 5c 6c 7c 8c 9c".parse::<CardSet>(),
+            Err(ParseCardError::TooManyCards { max: 7, position: 21 })
+        );
+    }
+
+    #[test]
+    fn test_deck_deterministic() {
+        let mut a = Deck::from_seed(42);
+        let mut b = Deck::from_seed(42);
+        assert_eq!(a.deal(52), b.deal(52));
+        assert_eq!(a.remaining(), 0);
+        assert_eq!(a.deal_one(), None);
+
+        let mut rng_a: StdRng = SeedableRng::seed_from_u64(7);
+        let mut rng_b: StdRng = SeedableRng::seed_from_u64(7);
+        assert_eq!(Deck::from_rng(&mut rng_a).deal(10), Deck::from_rng(&mut rng_b).deal(10));
+
+        a.reset();
+        b.reset();
+        let cards = a.deal(52);
+        assert_eq!(cards.iter().copied().collect::<CardMask>(), CardMask::FULL);
+        assert_eq!(cards, b.deal(52), "Decks with the same seed must reshuffle identically");
+    }
+
+    #[test]
+    fn test_deck_dealing() {
+        let dead: CardMask = "As Ks Qs".into();
+        let mut deck = Deck::from_seed(1);
+        deck.exclude(dead);
+        assert_eq!(deck.remaining(), 49);
+        assert!(deck.remaining_cards().is_disjoint(dead));
+
+        let top = deck.peek(2).to_vec();
+        assert_eq!(deck.burn(), Some(top[0]));
+        assert_eq!(deck.deal(1), vec![top[1]]);
+        assert_eq!(deck.remaining(), 47);
+
+        deck.reset();
+        assert_eq!(deck.remaining(), 49);
+        assert_eq!(deck.remaining_cards(), !dead);
+        assert_eq!(deck.dead_cards(), dead);
+    }
+}
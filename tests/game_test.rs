@@ -1,7 +1,15 @@
 
 #[cfg(test)]
 mod game_test {
-    use poker::round_state::{RoundState, RoundStage};
+    use poker::round_state::{Chips, RoundState, RoundStage};
+    use poker::table_config::{BettingStructure, TableConfig};
+    use poker::card::Deck;
+    use poker::showdown::PotResult;
+    use poker::pots::{Pot, Pots};
+    use poker::hand_history::{ForcedBet, HandEvent};
+    use poker::card_set::CardSet;
+    use poker::action::{Action, ActionError, LegalActions};
+    use rand::{SeedableRng, rngs::StdRng, Rng};
 
     // TODO: add more tests beside this complex case
     #[test]
@@ -10,7 +18,7 @@ mod game_test {
             player_count: 8,
             stage: RoundStage::PreFlop,
             community_cards: "As Ks 5h 6c 8c".into(),
-            player_cards: vec![
+            player_cards: [
                 "Ah 4h", // 0 2nd
                 "Ac 3c", // 1 2nd
                 "Kh 5s", // 2 1st
@@ -20,8 +28,9 @@ mod game_test {
                 "8h 4d", // 6 4th
                 "6h 2d", // 7 3th
             ].iter().map(|&s| s.into()).collect(),
-            bet_chips:  vec![3.0, 50.0,  6.0, 10.0, 15.0, 110.0, 15.0, 130.0],
-            free_chips: vec![0.0,  0.0, 25.0,  0.0,  0.0,   0.0, 10.0,  20.0],
+            bet_chips:  vec![3, 50,  6, 10, 15, 110, 15, 130],
+            street_bets: vec![3, 50,  6, 10, 15, 110, 15, 130],
+            free_chips: vec![0,  0, 25,  0,  0,   0, 10,  20],
             folded: (1 << 2) | (1 << 6),
             ..Default::default()
         };
@@ -31,14 +40,614 @@ mod game_test {
 
         assert_eq!(
             round_state.bet_chips,
-            vec![0.0; 8],
+            vec![0; 8],
             "Bet chips should be removed when round is finished"
         );
 
         assert_eq!(
             round_state.free_chips,
-            vec![0.0, 105.0, 40.0, 27.0, 52.0, 0.0, 10.0, 160.0],
+            vec![0, 105, 25, 35, 59, 0, 10, 160],
             "Chips distributed incorrectly"
         );
+
+        // 20 of the 130 were never called, the folded players' chips stay in the pots
+        let showdown = round_state.showdown.as_ref().unwrap();
+        assert_eq!(showdown.uncalled_bet, Some((7, 20)));
+        assert_eq!(
+            showdown.pots.iter().map(|pot| (pot.amount, pot.winners.clone())).collect::<Vec<_>>(),
+            vec![(24, vec![3, 4]), (45, vec![3, 4]), (25, vec![4]), (105, vec![1]), (120, vec![7])]
+        );
+        assert!(showdown.hand(2).is_none() && showdown.hand(6).is_none(), "Folded hands aren't shown");
+    }
+
+    #[test]
+    pub fn test_showdown_result() {
+        let mut round_state = RoundState {
+            player_count: 3,
+            community_cards: "2c 7d 9h Js 3s".into(),
+            player_cards: ["As Ah", "Kd Kc", "Qd Qc"].iter().map(|&s| s.into()).collect(),
+            bet_chips:  vec![50, 100, 100],
+            street_bets: vec![50, 100, 100],
+            free_chips: vec![0, 0, 0],
+            ..Default::default()
+        };
+        while !round_state.is_finished() {
+            round_state.do_action(0.0);
+        }
+        assert_eq!(round_state.free_chips, vec![150, 100, 0]);
+
+        let showdown = round_state.showdown.as_ref().unwrap();
+        assert_eq!(showdown.pots, vec![
+            PotResult { amount: 150, eligible: 0b111, winners: vec![0], winnings: vec![150] },
+            PotResult { amount: 100, eligible: 0b110, winners: vec![1], winnings: vec![100] },
+        ]);
+        assert_eq!(showdown.net_winnings, vec![100, 0, -100]);
+        assert_eq!(showdown.hands.len(), 3);
+        let aces = showdown.hand(0).unwrap();
+        assert!(aces.hand_rank > showdown.hand(1).unwrap().hand_rank);
+        assert_eq!(aces.best_five(), "As Ah Js 9h 7d".into());
+    }
+
+    #[test]
+    pub fn test_pots() {
+        let pots = Pots::new(&[100, 300, 300, 50], 1 << 3, 0b1001);
+        assert_eq!(pots.pots, vec![
+            Pot { amount: 350, eligible: 0b0111 },
+            Pot { amount: 400, eligible: 0b0110 },
+        ]);
+        assert_eq!(pots.total(), 750);
+        assert!(Pots::new(&[0, 0], 0, 0).is_empty());
+        // Players with chips left can still match the highest bet
+        assert_eq!(Pots::new(&[50, 200, 120], 0, 0b001).pots, vec![
+            Pot { amount: 150, eligible: 0b111 },
+            Pot { amount: 220, eligible: 0b110 },
+        ]);
+
+        // Side pots while the round is being played, current street bets included
+        let mut round_state = RoundState::from_seed(vec![200, 500, 1000], 3);
+        assert_eq!(round_state.pots().pots, vec![Pot { amount: 150, eligible: 0b111 }]);
+        round_state.try_apply(0, Action::AllIn).unwrap();
+        round_state.try_apply(1, Action::AllIn).unwrap();
+        // The big blind hasn't acted yet and stays eligible for every pot
+        let pots = round_state.pots();
+        assert_eq!(pots.main_pot(), Some(&Pot { amount: 500, eligible: 0b111 }));
+        assert_eq!(pots.side_pots(), &[Pot { amount: 300, eligible: 0b110 }]);
+        assert_eq!(pots.to_string(), "main 500 (0, 1, 2), side 300 (1, 2)");
+    }
+
+    #[test]
+    pub fn test_odd_chip_split() {
+        let mut round_state = RoundState {
+            player_count: 3,
+            community_cards: "As Ks Qs Js Ts".into(),
+            player_cards: ["2c 3c", "2d 3d", "2h 3h"].iter().map(|&s| s.into()).collect(),
+            bet_chips:  vec![1, 100, 100],
+            street_bets: vec![1, 100, 100],
+            free_chips: vec![0, 0, 0],
+            folded: 1 << 0,
+            ..Default::default()
+        };
+        while !round_state.is_finished() {
+            round_state.do_action(0.0);
+        }
+        assert_eq!(round_state.free_chips, vec![0, 101, 100], "Odd chip goes to the first winner left of the button");
+        let pot = &round_state.showdown.as_ref().unwrap().pots[0];
+        assert_eq!((pot.amount, &pot.winners, &pot.winnings), (201, &vec![1, 2], &vec![101, 100]));
+    }
+
+    #[test]
+    pub fn test_big_blind_layer() {
+        let mut round_state = RoundState::new(vec![10.0, 2.5]);
+        assert_eq!(round_state.bet_chips, vec![50, 100]);
+        assert_eq!(round_state.free_chips, vec![950, 150]);
+        round_state.do_action(1.5);
+        assert_eq!(round_state.bet_chips, vec![200, 100]);
+        assert_eq!(round_state.config.chips_to_big_blinds(round_state.bet_chips[0]), 2.0);
+        assert_eq!(round_state.config.big_blinds_to_chips(0.255), 26);
+    }
+
+    #[test]
+    pub fn test_seeded_rounds_are_reproducible() {
+        let a = RoundState::from_seed(vec![1000; 6], 1234);
+        let b = RoundState::from_seed(vec![1000; 6], 1234);
+        assert_eq!(a.community_cards, b.community_cards);
+        assert_eq!(a.player_cards, b.player_cards);
+
+        let c = RoundState::from_seed(vec![1000; 6], 4321);
+        assert_ne!(a.player_cards, c.player_cards);
+    }
+
+    #[test]
+    pub fn test_explicit_cards_showdown() {
+        let player_cards: Vec<CardSet> = vec!["As Ah".into(), "Kd Kc".into()];
+        let mut round_state = RoundState::from_cards(vec![1000, 1000], player_cards.clone(), "2c 7d 9h Js 3s".into());
+        assert_eq!(round_state.player_cards, player_cards);
+        while !round_state.is_finished() {
+            round_state.do_action(0.0);
+        }
+        assert_eq!(round_state.free_chips, vec![1100, 900], "Aces should win the big blind");
+    }
+
+    #[test]
+    #[should_panic]
+    pub fn test_explicit_cards_must_be_unique() {
+        RoundState::from_cards(vec![1000, 1000], vec!["As Ah".into(), "As Kc".into()], "2c 7d 9h Js 3s".into());
+    }
+
+    #[test]
+    pub fn test_try_apply_rejects_illegal_actions() {
+        // Heads-up: seat 0 is the button and small blind and acts first pre-flop
+        let mut round_state = RoundState::from_cards(vec![1000, 1000], vec!["As Ah".into(), "Kd Kc".into()], "2c 7d 9h Js 3s".into());
+
+        assert_eq!(round_state.try_apply(1, Action::Call), Err(ActionError::OutOfTurn { seat: 1, turn: 0 }));
+        assert_eq!(round_state.try_apply(0, Action::Check), Err(ActionError::CannotCheck { call_amount: 50 }));
+        assert_eq!(round_state.try_apply(0, Action::Bet(300)), Err(ActionError::CannotBet));
+        // Raise-to totals, the small blind would only add 100
+        assert_eq!(round_state.try_apply(0, Action::Raise(150)), Err(ActionError::BelowMinimum { amount: 150, min: 200 }));
+        assert_eq!(round_state.try_apply(0, Action::Raise(1100)), Err(ActionError::NotEnoughChips { amount: 1050, available: 950 }));
+
+        let outcome = round_state.try_apply(0, Action::Raise(300)).unwrap();
+        assert_eq!(outcome.chips_added, 250);
+        assert!(!outcome.stage_changed);
+        assert!(round_state.try_apply(1, Action::Call).unwrap().stage_changed);
+
+        // Flop: big blind acts first
+        assert_eq!(round_state.try_apply(1, Action::Call), Err(ActionError::NothingToCall));
+        assert_eq!(round_state.try_apply(1, Action::Raise(200)), Err(ActionError::CannotRaise));
+        assert_eq!(round_state.try_apply(1, Action::Bet(50)), Err(ActionError::BelowMinimum { amount: 50, min: 100 }));
+        round_state.try_apply(1, Action::Bet(200)).unwrap();
+        round_state.try_apply(0, Action::AllIn).unwrap();
+        let outcome = round_state.try_apply(1, Action::Call).unwrap();
+        assert!(outcome.finished);
+        assert_eq!(round_state.try_apply(0, Action::Check), Err(ActionError::RoundFinished));
+        assert_eq!(round_state.free_chips, vec![2000, 0]);
+    }
+
+    #[test]
+    pub fn test_random_rounds_conserve_chips() {
+        let mut rng: StdRng = SeedableRng::seed_from_u64(0u64);
+        for player_count in 2..=9 {
+            for _ in 0..200 {
+                let stacks: Vec<Chips> = (0..player_count).map(|_| rng.gen_range(1..20_000)).collect();
+                let betting = [
+                    BettingStructure::NoLimit,
+                    BettingStructure::PotLimit,
+                    BettingStructure::FixedLimit { small_bet: 100, big_bet: 200, max_bets: 4 },
+                ][rng.gen_range(0..3)];
+                let config = TableConfig { button: rng.gen_range(0..player_count), betting, ..Default::default() };
+                let mut round_state = RoundState::with_config(config, stacks.clone(), &mut Deck::from_rng(&mut rng));
+                while !round_state.is_finished() {
+                    let f: f32 = rng.gen();
+                    let bet_size = if f < 0.1 { -1.0 } else if f < 0.75 { 0.0 } else { rng.gen_range(0.0..50.0) };
+                    round_state.do_action(bet_size);
+                }
+                assert_eq!(
+                    round_state.free_chips.iter().sum::<Chips>(),
+                    stacks.iter().sum::<Chips>(),
+                    "Chips were created or lost"
+                );
+                let showdown = round_state.showdown.as_ref().unwrap();
+                assert_eq!(showdown.uncontested, round_state.players_in_hand() == 1);
+                assert_eq!(showdown.hands.len(), if showdown.uncontested { 0 } else { round_state.players_in_hand() });
+                let history = round_state.history.as_ref().unwrap();
+                let streets = history.events.iter().filter(|event| matches!(event, HandEvent::Street { .. })).count();
+                assert_eq!(streets, showdown.final_stage as usize, "Every revealed street is recorded");
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_uncontested_pot() {
+        let player_cards: Vec<CardSet> = vec!["As Ah".into(), "Kd Kc".into(), "Qd Qc".into()];
+        let mut round_state = RoundState::from_cards(vec![1000, 1000, 1000], player_cards.clone(), "2c 7d 9h Js 3s".into());
+        round_state.try_apply(0, Action::Raise(300)).unwrap();
+        round_state.try_apply(1, Action::Fold).unwrap();
+        let outcome = round_state.try_apply(2, Action::Fold).unwrap();
+        assert!(outcome.finished && round_state.is_finished());
+        assert_eq!(round_state.free_chips, vec![1150, 950, 900]);
+        assert!(round_state.revealed_community_cards().is_empty());
+
+        let showdown = round_state.showdown.as_ref().unwrap();
+        assert!(showdown.uncontested);
+        assert_eq!(showdown.final_stage, RoundStage::PreFlop);
+        assert!(showdown.hands.is_empty(), "No cards are shown without a showdown");
+        assert_eq!(showdown.uncalled_bet, Some((0, 200)));
+        assert_eq!(showdown.pots, vec![PotResult { amount: 250, eligible: 0b001, winners: vec![0], winnings: vec![250] }]);
+        assert_eq!(showdown.net_winnings, vec![150, -50, -100]);
+
+        // Folding to a bet on the flop, the folded aces can't win the pot
+        let mut round_state = RoundState::from_cards(vec![1000, 1000, 1000], player_cards, "2c 7d 9h Js 3s".into());
+        round_state.try_apply(0, Action::Call).unwrap();
+        round_state.try_apply(1, Action::Call).unwrap();
+        round_state.try_apply(2, Action::Check).unwrap();
+        round_state.try_apply(1, Action::Bet(200)).unwrap();
+        round_state.try_apply(2, Action::Fold).unwrap();
+        assert!(!round_state.is_finished());
+        round_state.try_apply(0, Action::Fold).unwrap();
+        assert!(round_state.is_finished());
+        assert_eq!(round_state.revealed_community_cards().len(), 3);
+        assert_eq!(round_state.free_chips, vec![900, 1200, 900]);
+        assert_eq!(round_state.showdown.as_ref().unwrap().final_stage, RoundStage::Flop);
+    }
+
+    #[test]
+    pub fn test_hand_history() {
+        let player_cards: Vec<CardSet> = vec!["As Ah".into(), "Kd Kc".into()];
+        let mut round_state = RoundState::from_cards(vec![1000, 1000], player_cards.clone(), "2c 7d 9h Js 3s".into());
+        round_state.try_apply(0, Action::Raise(300)).unwrap();
+        round_state.try_apply(1, Action::Call).unwrap();
+        round_state.try_apply(1, Action::Check).unwrap();
+        round_state.try_apply(0, Action::Bet(200)).unwrap();
+        round_state.try_apply(1, Action::Fold).unwrap();
+
+        let history = round_state.history.as_ref().unwrap();
+        assert_eq!(history.events, vec![
+            HandEvent::Post { seat: 0, forced_bet: ForcedBet::SmallBlind, amount: 50 },
+            HandEvent::Post { seat: 1, forced_bet: ForcedBet::BigBlind, amount: 100 },
+            HandEvent::HoleCards { seat: 0, cards: "As Ah".into() },
+            HandEvent::HoleCards { seat: 1, cards: "Kd Kc".into() },
+            HandEvent::Action { seat: 0, stage: RoundStage::PreFlop, action: Action::Raise(300), chips_added: 250, pot: 400 },
+            HandEvent::Action { seat: 1, stage: RoundStage::PreFlop, action: Action::Call, chips_added: 200, pot: 600 },
+            HandEvent::Street { stage: RoundStage::Flop, cards: "2c 7d 9h".into() },
+            HandEvent::Action { seat: 1, stage: RoundStage::Flop, action: Action::Check, chips_added: 0, pot: 600 },
+            HandEvent::Action { seat: 0, stage: RoundStage::Flop, action: Action::Bet(200), chips_added: 200, pot: 800 },
+            HandEvent::Action { seat: 1, stage: RoundStage::Flop, action: Action::Fold, chips_added: 0, pot: 800 },
+        ]);
+        assert_eq!(history.actions().count(), 5);
+
+        let config = TableConfig { record_history: false, ..Default::default() };
+        let round_state = RoundState::with_config_and_cards(config, vec![1000, 1000], player_cards, "2c 7d 9h Js 3s".into());
+        assert!(round_state.history.is_none());
+    }
+
+    #[test]
+    pub fn test_legal_actions() {
+        let mut round_state = RoundState::from_cards(vec![1000, 1000, 250], vec!["As Ah".into(), "Kd Kc".into(), "Qd Qc".into()], "2c 7d 9h Js 3s".into());
+        // Button (seat 0) is first to act with 3 players
+        assert_eq!(round_state.legal_actions(), Some(LegalActions {
+            seat: 0,
+            can_fold: true,
+            can_check: false,
+            can_call: true,
+            call_amount: 100,
+            can_bet: false,
+            can_raise: true,
+            can_all_in: true,
+            min_raise_to: 200,
+            max_raise_to: 1000,
+        }));
+        round_state.try_apply(0, Action::Raise(400)).unwrap();
+
+        // Small blind: min raise is now 400 + 300
+        let legal = round_state.legal_actions().unwrap();
+        assert_eq!((legal.seat, legal.call_amount, legal.min_raise_to, legal.max_raise_to), (1, 350, 700, 1000));
+        assert!(legal.is_legal(Action::Raise(700)));
+        assert!(!legal.is_legal(Action::Raise(600)));
+        assert!(!legal.is_legal(Action::Bet(700)));
+        round_state.try_apply(1, Action::Call).unwrap();
+
+        // Big blind can't cover the call
+        let legal = round_state.legal_actions().unwrap();
+        assert_eq!((legal.seat, legal.call_amount, legal.can_raise), (2, 150, false));
+        assert_eq!(round_state.try_apply(2, Action::Raise(250)), Err(ActionError::RaiseNotAllowed));
+        round_state.try_apply(2, Action::Call).unwrap();
+
+        // Flop: min bet is back to the big blind
+        let legal = round_state.legal_actions().unwrap();
+        assert_eq!((legal.seat, legal.can_check, legal.can_bet, legal.min_raise_to), (1, true, true, 100));
+
+        while !round_state.is_finished() {
+            round_state.do_action(0.0);
+        }
+        assert_eq!(round_state.legal_actions(), None);
+    }
+
+    #[test]
+    pub fn test_no_raise_against_all_in_players() {
+        let mut round_state = RoundState::from_cards(
+            vec![1000, 1000, 5000],
+            vec!["As Ah".into(), "Kd Kc".into(), "Qd Qc".into()],
+            "2c 7d 9h Js 3s".into()
+        );
+        round_state.try_apply(0, Action::AllIn).unwrap();
+        round_state.try_apply(1, Action::AllIn).unwrap();
+
+        // Everyone else is all-in: the big blind can only call or fold
+        let legal = round_state.legal_actions().unwrap();
+        assert_eq!((legal.seat, legal.call_amount, legal.max_raise_to), (2, 900, 1000));
+        assert!(!legal.can_raise && !legal.can_all_in);
+        assert_eq!(round_state.try_apply(2, Action::Raise(1900)), Err(ActionError::RaiseNotAllowed));
+        assert_eq!(round_state.try_apply(2, Action::AllIn), Err(ActionError::RaiseNotAllowed));
+        let outcome = round_state.try_apply(2, Action::Call).unwrap();
+        assert!(outcome.finished && outcome.run_out);
+        assert_eq!(round_state.free_chips.iter().sum::<Chips>(), 7000);
+    }
+
+    #[test]
+    pub fn test_incomplete_raise() {
+        let player_cards: Vec<CardSet> = vec!["As Ah".into(), "Kd Kc".into(), "Qd Qc".into()];
+        let mut round_state = RoundState::from_cards(vec![1000, 1000, 170], player_cards, "2c 7d 9h Js 3s".into());
+        round_state.try_apply(0, Action::Call).unwrap();
+        round_state.try_apply(1, Action::Call).unwrap();
+        // The big blind's all-in is only 70 more, less than a full raise
+        round_state.try_apply(2, Action::AllIn).unwrap();
+
+        let legal = round_state.legal_actions().unwrap();
+        assert_eq!((legal.seat, legal.call_amount), (0, 70));
+        assert!(!legal.can_raise && !legal.can_all_in && !round_state.is_reopened(0));
+        assert_eq!(round_state.try_apply(0, Action::Raise(400)), Err(ActionError::RaiseNotAllowed));
+        assert_eq!(round_state.try_apply(0, Action::AllIn), Err(ActionError::RaiseNotAllowed));
+        round_state.try_apply(0, Action::Call).unwrap();
+        round_state.do_action(20.0); // Clipped to a call
+        assert_eq!(round_state.stage, RoundStage::Flop);
+        assert_eq!(round_state.bet_chips, vec![170, 170, 170]);
+
+        // Two incomplete all-ins that add up to a full raise do reopen the betting
+        let player_cards: Vec<CardSet> = vec!["As Ah".into(), "Kd Kc".into(), "Qd Qc".into(), "Jd Jc".into()];
+        let mut round_state = RoundState::from_cards(vec![340, 1000, 1000, 260], player_cards, "2c 7d 9h Js 3s".into());
+        for seat in [3, 0, 1] {
+            round_state.try_apply(seat, Action::Call).unwrap();
+        }
+        round_state.try_apply(2, Action::Check).unwrap();
+        round_state.try_apply(1, Action::Bet(100)).unwrap();
+        round_state.try_apply(2, Action::Call).unwrap();
+        round_state.try_apply(3, Action::AllIn).unwrap(); // 160
+        round_state.try_apply(0, Action::AllIn).unwrap(); // 240
+        let legal = round_state.legal_actions().unwrap();
+        assert_eq!((legal.seat, legal.call_amount, legal.min_raise_to), (1, 140, 340));
+        assert!(legal.can_raise);
+    }
+
+    #[test]
+    pub fn test_pot_limit() {
+        let config = TableConfig { betting: BettingStructure::PotLimit, ..Default::default() };
+        let player_cards: Vec<CardSet> = vec!["As Ah".into(), "Kd Kc".into()];
+        let mut round_state = RoundState::with_config_and_cards(config, vec![10_000, 10_000], player_cards, "2c 7d 9h Js 3s".into());
+
+        // Small blind: call 50 to make the pot 200, then raise by 200
+        let legal = round_state.legal_actions().unwrap();
+        assert_eq!((legal.min_raise_to, legal.max_raise_to), (200, 300));
+        assert!(!legal.can_all_in);
+        assert_eq!(round_state.try_apply(0, Action::Raise(301)), Err(ActionError::AboveMaximum { amount: 301, max: 300 }));
+        assert_eq!(round_state.try_apply(0, Action::AllIn), Err(ActionError::RaiseNotAllowed));
+        round_state.try_apply(0, Action::Raise(300)).unwrap();
+        assert_eq!(round_state.legal_actions().unwrap().max_raise_to, 900);
+        round_state.try_apply(1, Action::Call).unwrap();
+
+        // Flop: the pot is 600
+        let legal = round_state.legal_actions().unwrap();
+        assert_eq!((legal.seat, legal.min_raise_to, legal.max_raise_to), (1, 100, 600));
+        round_state.do_action(100.0); // Clipped to a pot sized bet
+        assert_eq!(round_state.street_bets, vec![0, 600]);
+    }
+
+    #[test]
+    pub fn test_fixed_limit() {
+        let betting = BettingStructure::FixedLimit { small_bet: 100, big_bet: 200, max_bets: 4 };
+        let config = TableConfig { betting, ..Default::default() };
+        let mut round_state = RoundState::with_config(config, vec![10_000; 3], &mut Deck::from_seed(3));
+
+        // Pre-flop raises are 100, the big blind counts as the first of 4 bets
+        let legal = round_state.legal_actions().unwrap();
+        assert_eq!((legal.seat, legal.min_raise_to, legal.max_raise_to), (0, 200, 200));
+        assert_eq!(round_state.try_apply(0, Action::Raise(300)), Err(ActionError::AboveMaximum { amount: 300, max: 200 }));
+        round_state.try_apply(0, Action::Raise(200)).unwrap();
+        round_state.try_apply(1, Action::Raise(300)).unwrap();
+        round_state.try_apply(2, Action::Raise(400)).unwrap();
+        assert_eq!(round_state.bets_on_street(), 4);
+        let legal = round_state.legal_actions().unwrap();
+        assert!(!legal.can_raise && !legal.can_all_in && legal.can_call);
+        round_state.try_apply(0, Action::Call).unwrap();
+        round_state.try_apply(1, Action::Call).unwrap();
+
+        // Flop: small bets, turn: big bets
+        assert_eq!(round_state.stage, RoundStage::Flop);
+        assert_eq!(round_state.try_apply(1, Action::Bet(150)), Err(ActionError::AboveMaximum { amount: 150, max: 100 }));
+        round_state.try_apply(1, Action::Bet(100)).unwrap();
+        round_state.try_apply(2, Action::Call).unwrap();
+        round_state.try_apply(0, Action::Call).unwrap();
+        assert_eq!(round_state.stage, RoundStage::Turn);
+        round_state.do_action(50.0); // Clipped to the big bet
+        assert_eq!(round_state.street_bets, vec![0, 200, 0]);
+    }
+
+    #[test]
+    pub fn test_antes_and_big_blind_ante() {
+        let config = TableConfig { small_blind: 100, big_blind: 200, ante: 25, big_blind_ante: 200, ..Default::default() };
+        let mut round_state = RoundState::with_config(config, vec![5000, 5000, 5000, 10], &mut Deck::from_seed(3));
+
+        // Seat 3 is all-in after posting 10 of the 25 ante
+        assert_eq!(round_state.bet_chips, vec![25, 125, 425, 10]);
+        assert_eq!(round_state.street_bets, vec![0, 100, 200, 0]);
+        assert_eq!(round_state.free_chips, vec![4975, 4875, 4575, 0]);
+
+        // Seat 3 can't act, so the button is first and calls the big blind, not the antes
+        let legal = round_state.legal_actions().unwrap();
+        assert_eq!((legal.seat, legal.call_amount, legal.min_raise_to), (0, 200, 400));
+        round_state.try_apply(0, Action::Call).unwrap();
+        round_state.try_apply(1, Action::Call).unwrap();
+        // Big blind has the option
+        assert!(round_state.legal_actions().unwrap().can_check);
+        round_state.try_apply(2, Action::Check).unwrap();
+        assert_eq!(round_state.stage, RoundStage::Flop);
+        assert_eq!(round_state.bet_chips, vec![225, 225, 425, 10]);
+
+        // A short big blind posts the big blind ante first and is all-in for 50 of the big blind
+        let config = TableConfig { small_blind: 100, big_blind: 200, big_blind_ante: 200, ..Default::default() };
+        let round_state = RoundState::with_config(config, vec![5000, 5000, 250], &mut Deck::from_seed(3));
+        assert_eq!(round_state.bet_chips, vec![0, 100, 250]);
+        assert_eq!(round_state.street_bets, vec![0, 100, 50]);
+        let legal = round_state.legal_actions().unwrap();
+        assert_eq!((legal.seat, legal.call_amount), (0, 200));
+    }
+
+    #[test]
+    pub fn test_short_blinds() {
+        // Big blind can only post 40, the small blind still has to complete to the full big blind
+        let mut round_state = RoundState::from_cards(vec![1000, 40], vec!["As Ah".into(), "Kd Kc".into()], "2c 7d 9h Js 3s".into());
+        assert_eq!(round_state.bet_chips, vec![50, 40]);
+        let legal = round_state.legal_actions().unwrap();
+        assert_eq!((legal.seat, legal.call_amount), (0, 50));
+        // The big blind is all-in, nobody is left to bet against: the board is run out
+        let outcome = round_state.try_apply(0, Action::Call).unwrap();
+        assert!(outcome.finished && outcome.run_out);
+        // The uncalled part is returned
+        assert_eq!(round_state.free_chips, vec![1040, 0]);
+    }
+
+    #[test]
+    pub fn test_all_in_run_out() {
+        let mut round_state = RoundState::from_cards(
+            vec![1000, 3000, 400],
+            vec!["As Ah".into(), "Kd Kc".into(), "Qd Qc".into()],
+            "2c 7d 9h Js 3s".into()
+        );
+        round_state.try_apply(0, Action::Call).unwrap();
+        round_state.try_apply(1, Action::Call).unwrap();
+        round_state.try_apply(2, Action::Check).unwrap();
+
+        // Flop: the lone player with chips behind isn't asked to act after calling the all-ins
+        round_state.try_apply(1, Action::Bet(200)).unwrap();
+        round_state.try_apply(2, Action::AllIn).unwrap();
+        assert!(!round_state.is_betting_closed());
+        round_state.try_apply(0, Action::AllIn).unwrap();
+        let outcome = round_state.try_apply(1, Action::Call).unwrap();
+        assert!(outcome.run_out && outcome.finished);
+        assert!(round_state.is_betting_closed());
+        assert_eq!(round_state.run_out_from, Some(RoundStage::Turn));
+        assert_eq!(round_state.run_out_streets(), vec![
+            (RoundStage::Turn, &["Js".into()][..]),
+            (RoundStage::River, &["3s".into()][..]),
+        ]);
+        assert_eq!(round_state.free_chips, vec![2400, 2000, 0]);
+
+        // All-in pre-flop: every street is dealt without betting
+        let mut round_state = RoundState::from_cards(vec![1000, 1000], vec!["As Ah".into(), "Kd Kc".into()], "2c 7d 9h Js 3s".into());
+        round_state.try_apply(0, Action::AllIn).unwrap();
+        round_state.try_apply(1, Action::Call).unwrap();
+        assert_eq!(round_state.run_out_from, Some(RoundStage::Flop));
+        assert_eq!(round_state.run_out_streets().len(), 3);
+        assert_eq!(round_state.showdown.as_ref().unwrap().final_stage, RoundStage::River);
+
+        // A fold that leaves one player with chips against an all-in closes the betting
+        let mut round_state = RoundState::from_cards(
+            vec![1000, 5000, 5000],
+            vec!["As Ah".into(), "Kd Kc".into(), "Qd Qc".into()],
+            "2c 7d 9h Js 3s".into()
+        );
+        round_state.try_apply(0, Action::AllIn).unwrap();
+        round_state.try_apply(1, Action::Call).unwrap();
+        round_state.try_apply(2, Action::Call).unwrap();
+        assert_eq!((round_state.stage, round_state.turn), (RoundStage::Flop, 1));
+        let outcome = round_state.try_apply(1, Action::Fold).unwrap();
+        assert!(outcome.run_out && outcome.finished);
+        assert_eq!(round_state.try_apply(2, Action::Bet(500)), Err(ActionError::RoundFinished));
+        assert_eq!(round_state.run_out_from, Some(RoundStage::Turn));
+        assert_eq!(round_state.free_chips, vec![3000, 4000, 4000]);
+
+        // Step-wise run-out: one street per advance_run_out call
+        let config = TableConfig { step_run_out: true, ..Default::default() };
+        let mut round_state = RoundState::with_config_and_cards(
+            config,
+            vec![1000, 1000],
+            vec!["As Ah".into(), "Kd Kc".into()],
+            "2c 7d 9h Js 3s".into()
+        );
+        round_state.try_apply(0, Action::AllIn).unwrap();
+        let outcome = round_state.try_apply(1, Action::Call).unwrap();
+        assert!(outcome.run_out && !outcome.finished);
+        assert!(round_state.is_running_out());
+        assert_eq!(round_state.legal_actions(), None);
+        assert_eq!(round_state.player_view(0).turn, None);
+        assert_eq!(round_state.try_apply(0, Action::Check), Err(ActionError::BettingClosed));
+        // The flop is dealt when the pre-flop betting ends, the turn and river one step each
+        assert_eq!(round_state.run_out_streets(), vec![(RoundStage::Flop, &["2c".into(), "7d".into(), "9h".into()][..])]);
+        assert_eq!(round_state.advance_run_out(), Some(RoundStage::Turn));
+        assert_eq!(round_state.run_out_streets().len(), 2);
+        assert!(!round_state.is_finished());
+        assert_eq!(round_state.advance_run_out(), Some(RoundStage::River));
+        assert!(round_state.is_finished() && !round_state.is_running_out());
+        assert_eq!(round_state.run_out_streets().len(), 3);
+        assert_eq!(round_state.advance_run_out(), None);
+        assert_eq!(round_state.free_chips, vec![2000, 0]);
+    }
+
+    #[test]
+    pub fn test_straddle() {
+        let config = TableConfig { straddle: Some(200), ..Default::default() };
+        let mut round_state = RoundState::with_config(config, vec![1000; 5], &mut Deck::from_seed(5));
+        assert_eq!(round_state.street_bets, vec![0, 50, 100, 200, 0]);
+
+        // Action starts left of the straddler, the min raise is based on the straddle
+        let legal = round_state.legal_actions().unwrap();
+        assert_eq!((legal.seat, legal.call_amount, legal.min_raise_to), (4, 200, 400));
+        round_state.try_apply(4, Action::Call).unwrap();
+        round_state.try_apply(0, Action::Call).unwrap();
+        round_state.try_apply(1, Action::Call).unwrap();
+        round_state.try_apply(2, Action::Call).unwrap();
+        // Straddler acts last pre-flop
+        assert_eq!(round_state.legal_actions().unwrap().seat, 3);
+        round_state.try_apply(3, Action::Check).unwrap();
+        assert_eq!(round_state.stage, RoundStage::Flop);
+        assert_eq!(round_state.legal_actions().unwrap().seat, 1);
+    }
+
+    #[test]
+    pub fn test_button_position() {
+        // 6 players, button on seat 4: SB 5, BB 0, UTG 1
+        let config = TableConfig { button: 4, ..Default::default() };
+        let mut round_state = RoundState::with_config(config, vec![1000; 6], &mut Deck::from_seed(9));
+        assert_eq!(round_state.street_bets, vec![100, 0, 0, 0, 0, 50]);
+        let mut acted = vec![];
+        while round_state.stage == RoundStage::PreFlop {
+            acted.push(round_state.turn);
+            round_state.do_action(0.0);
+        }
+        assert_eq!(acted, vec![1, 2, 3, 4, 5, 0], "Big blind should act last pre-flop");
+        // After the flop the first player left of the button begins
+        assert_eq!(round_state.turn, 5);
+
+        // Heads-up, button on seat 1: seat 1 posts the small blind and acts first pre-flop, last after
+        let config = TableConfig { button: 1, ..Default::default() };
+        let mut round_state = RoundState::with_config(config, vec![1000; 2], &mut Deck::from_seed(9));
+        assert_eq!(round_state.street_bets, vec![100, 50]);
+        assert_eq!(round_state.turn, 1);
+        round_state.do_action(0.0);
+        round_state.do_action(0.0);
+        assert_eq!((round_state.stage, round_state.turn), (RoundStage::Flop, 0));
+    }
+
+    #[test]
+    pub fn test_sitting_out() {
+        // Seat 1 sits out and seat 3 has no chips: blinds are posted by seats 2 and 4
+        let config = TableConfig { button: 0, sitting_out: 1 << 1, ..Default::default() };
+        let mut round_state = RoundState::with_config(config, vec![1000, 1000, 1000, 0, 1000], &mut Deck::from_seed(2));
+        assert_eq!(round_state.sitting_out, (1 << 1) | (1 << 3));
+        assert_eq!(round_state.street_bets, vec![0, 0, 50, 0, 100]);
+        assert_eq!(round_state.player_cards[1].len(), 0);
+        assert_eq!(round_state.player_cards[3].len(), 0);
+        assert_eq!(round_state.turn, 0);
+
+        while !round_state.is_finished() {
+            round_state.do_action(0.0);
+        }
+        assert_eq!(round_state.free_chips[1], 1000);
+        assert_eq!(round_state.free_chips[3], 0);
+        assert_eq!(round_state.free_chips.iter().sum::<Chips>(), 4000);
+
+        // Heads-up with a dead button: the two remaining players post the blinds in order
+        let config = TableConfig { button: 0, sitting_out: 1 << 0, ..Default::default() };
+        let mut round_state = RoundState::with_config(config, vec![1000; 3], &mut Deck::from_seed(2));
+        assert_eq!(round_state.street_bets, vec![0, 50, 100]);
+        assert_eq!(round_state.turn, 1);
+        // The small blind has the button: the big blind acts first after the flop
+        round_state.try_apply(1, Action::Call).unwrap();
+        round_state.try_apply(2, Action::Check).unwrap();
+        assert_eq!(round_state.stage, RoundStage::Flop);
+        assert_eq!(round_state.turn, 2);
+        round_state.try_apply(2, Action::Check).unwrap();
+        assert_eq!(round_state.turn, 1);
+        round_state.try_apply(1, Action::Check).unwrap();
+        assert_eq!((round_state.stage, round_state.turn), (RoundStage::Turn, 2));
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,47 @@
+#[cfg(test)]
+mod hand_rank_test {
+    use poker::card_set::CardSet;
+    use poker::hand_rank::{HandCategory, HandRank};
+
+    fn hand_rank(cards: &str) -> HandRank {
+        CardSet::from(cards).as_canonical().hand_rank()
+    }
+
+    #[test]
+    pub fn test_describe() {
+        for (cards, category, ranks, description) in [
+            ("As Kd 9h 7c 4s 3d 2c", HandCategory::HighCard, vec![12, 11, 7, 5, 2], "High Card, Ace-King-Nine-Seven-Four"),
+            ("Kh Kd 9h 7c 4s 3d 2c", HandCategory::Pair, vec![11, 7, 5, 2], "Pair, Kings, Nine-Seven-Four kickers"),
+            ("Kh Kd 5h 5c As 3d 2c", HandCategory::TwoPair, vec![11, 3, 12], "Two Pair, Kings and Fives, Ace kicker"),
+            ("6h 6d 6s 9c As 3d 2c", HandCategory::ThreeOfAKind, vec![4, 12, 7], "Three of a Kind, Sixes, Ace-Nine kickers"),
+            ("As 2d 3h 4c 5s Kd Kc", HandCategory::Straight, vec![3], "Straight, Five high"),
+            ("Ts Jd Qh Kc As 2d 2c", HandCategory::Straight, vec![12], "Straight, Ace high"),
+            ("As Ts 8s 4s 2s Kd Kc", HandCategory::Flush, vec![12, 8, 6, 2, 0], "Flush, Ace-Ten-Eight-Four-Two"),
+            ("Kh Kd Ks 5c 5s 5d 2c", HandCategory::FullHouse, vec![11, 3], "Full House, Kings full of Fives"),
+            ("7h 7d 7s 7c Qs 5d 2c", HandCategory::FourOfAKind, vec![5, 10], "Four of a Kind, Sevens, Queen kicker"),
+            ("5h 6h 7h 8h 9h 5d 2c", HandCategory::StraightFlush, vec![7], "Straight Flush, Nine high"),
+            ("Th Jh Qh Kh Ah 5d 2c", HandCategory::StraightFlush, vec![12], "Royal Flush"),
+        ] {
+            let rank = hand_rank(cards);
+            assert_eq!(rank.category(), category, "{}", cards);
+            assert_eq!(rank.ranks(), ranks, "{}", cards);
+            assert_eq!(rank.describe(), description);
+        }
+    }
+
+    #[test]
+    pub fn test_order() {
+        let ranks: Vec<HandRank> = [
+            "As Kd 9h 7c 4s 3d 2c",
+            "Ah Kc 9d 7d 5s 3d 2c",
+            "2h 2d 9h 7c 4s 3d Jc",
+            "Kh Kd 5h 5c 4s 3d 2c",
+            "Kh Kd 5h 5c As 3d 2c",
+            "As 2d 3h 4c 5s Kd Kc",
+            "Kh Kd Ks 5c 5s 5d 2c",
+            "Th Jh Qh Kh Ah 5d 2c",
+        ].iter().map(|&cards| hand_rank(cards)).collect();
+        assert!(ranks.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(ranks[0].category() < ranks[7].category());
+    }
+}
@@ -0,0 +1,54 @@
+#[cfg(test)]
+mod player_view_test {
+    use poker::action::Action;
+    use poker::card_set::CardSet;
+    use poker::hand_history::HandEvent;
+    use poker::round_state::RoundState;
+
+    fn round(hands: &[&str], board: &str) -> RoundState {
+        RoundState::from_cards(vec![1000, 1000, 1000], hands.iter().map(|&s| s.into()).collect(), board.into())
+    }
+
+    #[test]
+    pub fn test_only_own_cards() {
+        let mut round_state = round(&["As Ah", "Kd Kc", "Qd Qc"], "2c 7d 9h Js 3s");
+        round_state.try_apply(0, Action::Call).unwrap();
+        let view = round_state.player_view(1);
+        assert_eq!(view.hole_cards, CardSet::from("Kd Kc"));
+        assert_eq!(view.community_cards.len(), 0);
+        assert!(view.is_my_turn());
+        assert_eq!(view.legal_actions.map(|legal| legal.seat), Some(1));
+        let history = view.history.as_ref().unwrap();
+        let hole_cards: Vec<&HandEvent> = history.events.iter().filter(|event| matches!(event, HandEvent::HoleCards { .. })).collect();
+        assert_eq!(hole_cards, vec![&HandEvent::HoleCards { seat: 1, cards: "Kd Kc".into() }]);
+
+        let view = round_state.player_view(2);
+        assert!(!view.is_my_turn());
+        assert!(view.legal_actions.is_none());
+
+        round_state.try_apply(1, Action::Call).unwrap();
+        round_state.try_apply(2, Action::Check).unwrap();
+        assert_eq!(round_state.player_view(0).community_cards, CardSet::from("2c 7d 9h"));
+    }
+
+    #[test]
+    pub fn test_info_set_key() {
+        let play = |round_state: &mut RoundState, raise: bool| {
+            round_state.try_apply(0, if raise { Action::Raise(300) } else { Action::Call }).unwrap();
+            round_state.try_apply(1, Action::Call).unwrap();
+        };
+        let mut a = round(&["As Ah", "Kd Kc", "Qd Qc"], "2c 7d 9h Js 3s");
+        let mut b = round(&["Ah As", "5d 4c", "Qd Qc"], "2c 7d 9h Js 3s");
+        let mut c = round(&["As Ah", "Kd Kc", "Qd Qc"], "2c 7d 9h Js 3s");
+        play(&mut a, true);
+        play(&mut b, true);
+        play(&mut c, false);
+        // Other players' cards and the order of the hole cards don't matter, the actions do
+        assert_eq!(a.player_view(0).info_set_key(), b.player_view(0).info_set_key());
+        assert_ne!(a.player_view(0).info_set_key(), c.player_view(0).info_set_key());
+        assert_ne!(a.player_view(0).info_set_key(), a.player_view(2).info_set_key());
+        assert_ne!(a.player_view(1).info_set_key(), b.player_view(1).info_set_key());
+        // Stable across runs and platforms
+        assert_eq!(a.player_view(0).info_set_key(), 11177356657749023224);
+    }
+}
@@ -0,0 +1,309 @@
+#[cfg(test)]
+mod pokerstars_test {
+    use poker::action::Action;
+    use poker::card::Deck;
+    use poker::card_set::CardSet;
+    use poker::hand_history::{HandEvent, HandHistory};
+    use poker::pokerstars::{export_hand, parse_hand, parse_hands, replay, ExportError, HandInfo};
+    use poker::round_state::{Chips, RoundState};
+    use poker::table_config::{BettingStructure, TableConfig};
+
+    fn cards(hands: &[&str]) -> Vec<CardSet> {
+        hands.iter().map(|&s| s.into()).collect()
+    }
+
+    #[test]
+    pub fn test_export_uncontested() {
+        let mut round_state = RoundState::from_cards(vec![1000, 1000], cards(&["As Ah", "Kd Kc"]), "2c 7d 9h Js 3s".into());
+        assert_eq!(export_hand(&round_state, &HandInfo::default()), Err(ExportError::NotFinished));
+        round_state.try_apply(0, Action::Raise(300)).unwrap();
+        round_state.try_apply(1, Action::Call).unwrap();
+        round_state.try_apply(1, Action::Check).unwrap();
+        round_state.try_apply(0, Action::Bet(200)).unwrap();
+        round_state.try_apply(1, Action::Fold).unwrap();
+
+        let info = HandInfo {
+            hand_id: 42,
+            player_names: vec!["Alice".to_owned(), "Bob".to_owned()],
+            hero: Some(1),
+            ..Default::default()
+        };
+        let expected = "\
+PokerStars Hand #42:  Hold'em No Limit (50/100) - 1970/01/01 00:00:00 ET
+Table 'Table' 2-max Seat #1 is the button
+Seat 1: Alice (1000 in chips)
+Seat 2: Bob (1000 in chips)
+Alice: posts small blind 50
+Bob: posts big blind 100
+*** HOLE CARDS ***
+Dealt to Bob [Kd Kc]
+Alice: raises 200 to 300
+Bob: calls 200
+*** FLOP *** [2c 7d 9h]
+Bob: checks
+Alice: bets 200
+Bob: folds
+Uncalled bet (200) returned to Alice
+Alice collected 600 from pot
+Alice: doesn't show hand
+*** SUMMARY ***
+Total pot 600 | Rake 0
+Board [2c 7d 9h]
+Seat 1: Alice (button) (small blind) collected (600)
+Seat 2: Bob (big blind) folded on the Flop
+";
+        assert_eq!(export_hand(&round_state, &info).unwrap(), expected);
+    }
+
+    #[test]
+    pub fn test_export_showdown() {
+        let mut round_state = RoundState::from_cards(
+            vec![1000, 3000, 400],
+            cards(&["As Ah", "Kd Kc", "Qd Qc"]),
+            "2c 7d 9h Js 3s".into()
+        );
+        round_state.try_apply(0, Action::Call).unwrap();
+        round_state.try_apply(1, Action::Call).unwrap();
+        round_state.try_apply(2, Action::Check).unwrap();
+        round_state.try_apply(1, Action::Bet(200)).unwrap();
+        round_state.try_apply(2, Action::AllIn).unwrap();
+        round_state.try_apply(0, Action::AllIn).unwrap();
+        round_state.try_apply(1, Action::Call).unwrap();
+
+        let text = export_hand(&round_state, &HandInfo::default()).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        for line in [
+            "Player 3: raises 100 to 300 and is all-in",
+            "Player 1: raises 600 to 900 and is all-in",
+            "*** RIVER *** [2c 7d 9h Js] [3s]",
+            "Player 2: shows [Kd Kc] (a pair of Kings)",
+            "Player 1 collected 1200 from side pot",
+            "Player 1 collected 1200 from main pot",
+            "Total pot 2400 Main pot 1200. Side pot 1200. | Rake 0",
+            "Seat 1: Player 1 (button) showed [As Ah] and won (2400) with a pair of Aces",
+            "Seat 3: Player 3 (big blind) showed [Qd Qc] and lost with a pair of Queens",
+        ] {
+            assert!(lines.contains(&line), "Missing '{}' in:\n{}", line, text);
+        }
+
+        // The uncalled bet is returned before the run-out
+        let mut round_state = RoundState::from_cards(
+            vec![1000, 3000, 400],
+            cards(&["As Ah", "Kd Kc", "Qd Qc"]),
+            "2c 7d 9h Js 3s".into()
+        );
+        round_state.try_apply(0, Action::Call).unwrap();
+        round_state.try_apply(1, Action::Call).unwrap();
+        round_state.try_apply(2, Action::Check).unwrap();
+        round_state.try_apply(1, Action::Bet(200)).unwrap();
+        round_state.try_apply(2, Action::AllIn).unwrap();
+        round_state.try_apply(0, Action::AllIn).unwrap();
+        round_state.try_apply(1, Action::Fold).unwrap();
+        let text = export_hand(&round_state, &HandInfo::default()).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        let uncalled = lines.iter().position(|&line| line == "Uncalled bet (600) returned to Player 1");
+        assert!(uncalled.unwrap() < lines.iter().position(|&line| line.starts_with("*** TURN ***")).unwrap(), "{}", text);
+
+        // Side pots are numbered when there are several
+        let mut round_state = RoundState::from_cards(
+            vec![1000, 3000, 400, 200],
+            cards(&["As Ah", "Kd Kc", "Qd Qc", "Jd Jc"]),
+            "2c 7d 9h 8s 3s".into()
+        );
+        round_state.try_apply(3, Action::AllIn).unwrap();
+        round_state.try_apply(0, Action::AllIn).unwrap();
+        round_state.try_apply(1, Action::Call).unwrap();
+        round_state.try_apply(2, Action::AllIn).unwrap();
+        let text = export_hand(&round_state, &HandInfo::default()).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        for line in [
+            "Player 1 collected 1200 from side pot-2",
+            "Player 1 collected 600 from side pot-1",
+            "Player 1 collected 800 from main pot",
+            "Total pot 2600 Main pot 800. Side pot-1 600. Side pot-2 1200. | Rake 0",
+        ] {
+            assert!(lines.contains(&line), "Missing '{}' in:\n{}", line, text);
+        }
+
+        // Blinds that put everyone all-in: the uncalled bet is returned before the flop
+        let round_state = RoundState::from_cards(vec![30, 40], cards(&["As Ah", "Kd Kc"]), "2c 7d 9h Js 3s".into());
+        assert!(round_state.is_finished());
+        let text = export_hand(&round_state, &HandInfo::default()).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        let uncalled = lines.iter().position(|&line| line == "Uncalled bet (10) returned to Player 2");
+        assert_eq!(uncalled.map(|i| lines[i - 1]), Some("Dealt to Player 2 [Kd Kc]"), "{}", text);
+        assert_eq!(lines[uncalled.unwrap() + 1], "*** FLOP *** [2c 7d 9h]");
+        let replay = replay(&parse_hand(&text).unwrap()).unwrap();
+        assert_eq!(replay.divergences, vec![]);
+
+        let config = TableConfig { record_history: false, ..Default::default() };
+        let mut round_state = RoundState::with_config_and_cards(config, vec![1000, 1000], cards(&["As Ah", "Kd Kc"]), "2c 7d 9h Js 3s".into());
+        round_state.try_apply(0, Action::Fold).unwrap();
+        assert_eq!(export_hand(&round_state, &HandInfo::default()), Err(ExportError::NoHistory));
+    }
+
+    #[test]
+    pub fn test_import_round_trip() {
+        let mut round_state = RoundState::from_cards(
+            vec![1000, 3000, 400],
+            cards(&["As Ah", "Kd Kc", "Qd Qc"]),
+            "2c 7d 9h Js 3s".into()
+        );
+        round_state.try_apply(0, Action::Raise(250)).unwrap();
+        round_state.try_apply(1, Action::Call).unwrap();
+        round_state.try_apply(2, Action::Call).unwrap();
+        round_state.try_apply(1, Action::Bet(200)).unwrap();
+        round_state.try_apply(2, Action::AllIn).unwrap();
+        round_state.try_apply(0, Action::AllIn).unwrap();
+        round_state.try_apply(1, Action::Fold).unwrap();
+        let info = HandInfo { hero: Some(1), ..Default::default() };
+        let text = export_hand(&round_state, &info).unwrap();
+
+        let hand = parse_hand(&text).unwrap();
+        assert_eq!(hand.info.hero, Some(1));
+        assert_eq!(hand.stacks, vec![1000, 3000, 400]);
+        assert_eq!(hand.hole_cards, vec![Some("As Ah".into()), Some("Kd Kc".into()), Some("Qd Qc".into())]);
+        // All-ins are logged as the call, bet or raise they amount to
+        let chips = |history: &HandHistory| -> Vec<(usize, Chips, Chips)> {
+            history.actions().map(|event| match *event {
+                HandEvent::Action { seat, chips_added, pot, .. } => (seat, chips_added, pot),
+                _ => unreachable!(),
+            }).collect()
+        };
+        assert_eq!(chips(&hand.history), chips(round_state.history.as_ref().unwrap()));
+        let replay = replay(&hand).unwrap();
+        assert_eq!(replay.divergences, vec![]);
+        assert_eq!(replay.round.free_chips, round_state.free_chips);
+
+        // Two hands in one file, line numbers count from the start of the file
+        let hands = parse_hands(&format!("{}\n\n{}", text, text));
+        assert_eq!(hands.len(), 2);
+        assert_eq!(hands[1].as_ref().unwrap().line, text.lines().count() + 3);
+
+        // Without a hero everyone's hole cards are written and there is still no hero after importing
+        let without_hero = parse_hand(&export_hand(&round_state, &HandInfo::default()).unwrap()).unwrap();
+        assert_eq!(without_hero.info.hero, None);
+        assert_eq!(without_hero.hole_cards, hand.hole_cards);
+
+        // A byte order mark before the first header is skipped
+        let with_bom = parse_hand(&format!("\u{feff}{}", text)).unwrap();
+        assert_eq!((with_bom.line, with_bom.stacks), (1, vec![1000, 3000, 400]));
+    }
+
+    // Plays the actions in turn, then checks and calls until the round is finished
+    fn play(config: TableConfig, stacks: Vec<Chips>, actions: &[Action]) -> RoundState {
+        let mut round_state = RoundState::with_config(config, stacks, &mut Deck::from_seed(7));
+        for &action in actions {
+            round_state.try_apply(round_state.turn as usize, action).unwrap();
+        }
+        while !round_state.is_finished() {
+            round_state.do_action(0.0);
+        }
+        round_state
+    }
+
+    // Exports the round, imports it again and replays it without divergences
+    fn round_trip(round_state: &RoundState) -> TableConfig {
+        let text = export_hand(round_state, &HandInfo::default()).unwrap();
+        let hand = parse_hand(&text).unwrap();
+        let replay = replay(&hand).unwrap();
+        assert_eq!(replay.divergences, vec![], "{}", text);
+        assert_eq!(replay.round.free_chips, round_state.free_chips, "{}", text);
+        hand.config
+    }
+
+    #[test]
+    pub fn test_round_trip_forced_bets() {
+        // A short all-in big blind doesn't change the fixed-limit stakes
+        let betting = BettingStructure::FixedLimit { small_bet: 100, big_bet: 200, max_bets: 4 };
+        let config = TableConfig { button: 1, betting, ..Default::default() };
+        let config = round_trip(&play(config, vec![60, 1000, 1000], &[Action::Call, Action::Call]));
+        assert_eq!((config.small_blind, config.big_blind), (50, 100));
+
+        // The small blind is all-in with the ante and posts no blind, a later player is all-in after the ante
+        let config = TableConfig { ante: 20, ..Default::default() };
+        let config = round_trip(&play(config, vec![1000, 15, 1000, 60], &[Action::AllIn, Action::Call]));
+        assert_eq!((config.ante, config.dead_small_blind), (20, false));
+
+        // Heads-up the big blind is all-in with the big blind ante
+        let config = TableConfig { big_blind_ante: 50, ..Default::default() };
+        let config = round_trip(&play(config, vec![1400, 120], &[Action::Call]));
+        assert_eq!((config.ante, config.big_blind_ante), (0, 50));
+
+        // Straddles, a short all-in straddle keeps the straddle size
+        let config = TableConfig { straddle: Some(200), ..Default::default() };
+        let config = round_trip(&play(config.clone(), vec![1000; 4], &[Action::Raise(600), Action::Call]));
+        assert_eq!(config.straddle, Some(200));
+        let config = round_trip(&play(config, vec![1000, 1000, 1000, 150], &[Action::Call, Action::Fold]));
+        assert_eq!(config.straddle, Some(200));
+
+        // Sitting out on the button (dead button) and in the blinds
+        let config = TableConfig { button: 1, sitting_out: (1 << 1) | (1 << 3), ..Default::default() };
+        let config = round_trip(&play(config, vec![1000; 5], &[Action::Raise(300)]));
+        assert_eq!((config.button, config.sitting_out, config.dead_small_blind), (1, (1 << 1) | (1 << 3), false));
+
+        // Dead small blind, also when the big blind is all-in with the ante
+        let config = TableConfig { dead_small_blind: true, ..Default::default() };
+        assert!(round_trip(&play(config, vec![1000; 4], &[Action::Call, Action::Call])).dead_small_blind);
+        let config = TableConfig { ante: 40, button: 1, dead_small_blind: true, ..Default::default() };
+        assert!(round_trip(&play(config, vec![4000, 900, 4, 2900], &[Action::Raise(300), Action::Call])).dead_small_blind);
+    }
+
+    #[test]
+    pub fn test_import_errors() {
+        let mut round_state = RoundState::from_cards(vec![1000, 1000], cards(&["As Ah", "Kd Kc"]), "2c 7d 9h Js 3s".into());
+        round_state.try_apply(0, Action::Raise(300)).unwrap();
+        round_state.try_apply(1, Action::Fold).unwrap();
+        let text = export_hand(&round_state, &HandInfo::default()).unwrap();
+
+        let broken = text.replace("Player 2: folds", "Player 2: dances");
+        let error = parse_hand(&broken).unwrap_err();
+        assert_eq!(error.to_string(), "line 11: unrecognized action 'dances'");
+
+        let out_of_turn = text.replace("Player 1: raises", "Player 2: raises");
+        let error = replay(&parse_hand(&out_of_turn).unwrap()).unwrap_err();
+        assert_eq!(error.line, 10);
+
+        let wrong_collected = text.replace("collected 200 from pot", "collected 250 from pot");
+        let divergences = replay(&parse_hand(&wrong_collected).unwrap()).unwrap().divergences;
+        assert_eq!(divergences.len(), 1);
+        assert_eq!(divergences[0].to_string(), "line 13: Player 1 collected 250, replay computed 200");
+    }
+
+    #[test]
+    pub fn test_import_cash_game() {
+        // Amounts in dollars are imported in cents
+        let text = "\
+PokerStars Hand #2093: Hold'em No Limit ($0.01/$0.02 USD) - 2020/01/01 12:00:00 ET
+Table 'Alpha II' 6-max Seat #3 is the button
+Seat 1: john doe ($2 in chips)
+Seat 3: bob ($1.50 in chips)
+Seat 5: eve ($2.47 in chips) is sitting out
+Seat 6: zed ($3 in chips)
+zed: posts small blind $0.01
+john doe: posts big blind $0.02
+*** HOLE CARDS ***
+Dealt to bob [Ah Kh]
+bob: raises $0.04 to $0.06
+zed: folds
+john doe: calls $0.04
+*** FLOP *** [2c 7d 9h]
+john doe: checks
+bob: bets $0.10
+john doe: folds
+Uncalled bet ($0.10) returned to bob
+bob collected $0.13 from pot
+bob: doesn't show hand
+*** SUMMARY ***
+Total pot $0.13 | Rake $0
+Board [2c 7d 9h]
+Seat 1: john doe (big blind) folded on the Flop
+";
+        let hand = parse_hand(text).unwrap();
+        assert_eq!((hand.config.small_blind, hand.config.big_blind, hand.config.button), (1, 2, 2));
+        assert_eq!(hand.config.sitting_out, 0b11010);
+        let replay = replay(&hand).unwrap();
+        assert_eq!(replay.divergences, vec![]);
+        assert_eq!(replay.round.free_chips, vec![194, 0, 157, 0, 247, 299]);
+    }
+}
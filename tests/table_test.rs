@@ -0,0 +1,109 @@
+#[cfg(test)]
+mod table_test {
+    use poker::action::Action;
+    use poker::round_state::Chips;
+    use poker::table::{Table, TableError};
+    use poker::table_config::TableConfig;
+
+    fn play_hand(table: &mut Table, action: Action) {
+        let round_state = table.start_hand().unwrap();
+        while let Some(legal) = round_state.legal_actions() {
+            let action = if legal.is_legal(action) { action } else if legal.can_check { Action::Check } else { Action::Call };
+            round_state.try_apply(legal.seat, action).unwrap();
+        }
+    }
+
+    #[test]
+    pub fn test_button_moves() {
+        let mut table = Table::from_seed(TableConfig::default(), 6, 1);
+        for seat in 0..4 {
+            table.join(seat, 1000).unwrap();
+        }
+        for hand in 0..8 {
+            play_hand(&mut table, Action::Call);
+            let round_state = table.round.as_ref().unwrap();
+            assert_eq!(round_state.button as usize, hand % 4);
+            table.finish_hand().unwrap();
+
+            let stacks: Chips = table.seats.iter().flatten().map(|seat| seat.stack).sum();
+            let net_results: i64 = table.seats.iter().flatten().map(|seat| seat.net_result).sum();
+            assert_eq!((stacks, net_results), (4000, 0));
+        }
+        assert_eq!(table.hands_played, 8);
+        assert!(table.seats.iter().flatten().all(|seat| seat.hands_played == 8));
+    }
+
+    #[test]
+    pub fn test_dead_button() {
+        let mut table = Table::from_seed(TableConfig::default(), 5, 2);
+        for seat in 0..5 {
+            table.join(seat, 1000).unwrap();
+        }
+        assert_eq!(table.start_hand().unwrap().bet_chips, vec![0, 50, 100, 0, 0]);
+        table.round.as_mut().unwrap().do_action(-1.0);
+        table.round.as_mut().unwrap().do_action(-1.0);
+        table.round.as_mut().unwrap().do_action(-1.0);
+        table.round.as_mut().unwrap().do_action(-1.0);
+        table.finish_hand().unwrap();
+
+        // The big blind left: the big blind still moves one seat, the small blind is dead
+        table.leave(2).unwrap();
+        let round_state = table.start_hand().unwrap();
+        assert_eq!(round_state.bet_chips, vec![0, 0, 0, 100, 0]);
+        assert_eq!(round_state.button, 1);
+        while round_state.legal_actions().is_some() {
+            round_state.do_action(-1.0);
+        }
+        table.finish_hand().unwrap();
+
+        // The button is dead on the empty seat
+        let round_state = table.start_hand().unwrap();
+        assert_eq!(round_state.bet_chips, vec![0, 0, 0, 50, 100]);
+        assert_eq!(round_state.button, 2);
+    }
+
+    #[test]
+    pub fn test_join_and_leave() {
+        let mut table = Table::from_seed(TableConfig::default(), 4, 3);
+        assert_eq!(table.join(4, 1000), Err(TableError::NoSuchSeat { seat: 4 }));
+        assert_eq!(table.join(0, 0), Err(TableError::NoChips));
+        table.join(0, 1000).unwrap();
+        assert_eq!(table.join(0, 1000), Err(TableError::SeatTaken { seat: 0 }));
+        assert_eq!(table.start_hand().err(), Some(TableError::NotEnoughPlayers));
+
+        table.join(1, 1000).unwrap();
+        table.start_hand().unwrap();
+        assert_eq!(table.finish_hand(), Err(TableError::HandNotFinished));
+        assert_eq!(table.leave(0), Err(TableError::HandInProgress));
+        // Joining during a hand, dealt in from the next hand on
+        table.join(2, 500).unwrap();
+        assert!(!table.is_in_hand(2));
+        table.set_sitting_out(1, true).unwrap();
+        while table.round.as_ref().unwrap().legal_actions().is_some() {
+            table.round.as_mut().unwrap().do_action(0.0);
+        }
+        table.finish_hand().unwrap();
+
+        let round_state = table.start_hand().unwrap();
+        assert_eq!(round_state.sitting_out, 0b1010);
+        assert_eq!(table.leave(1).map(|seat| seat.hands_played), Ok(1));
+        assert_eq!(table.leave(3), Err(TableError::SeatEmpty { seat: 3 }));
+    }
+
+    #[test]
+    pub fn test_busted_players_are_removed() {
+        let mut table = Table::from_seed(TableConfig::default(), 3, 4);
+        table.join(0, 1000).unwrap();
+        table.join(2, 1000).unwrap();
+        let mut busted = vec![];
+        while busted.is_empty() {
+            play_hand(&mut table, Action::AllIn);
+            busted = table.finish_hand().unwrap();
+        }
+        let (seat, player) = &busted[0];
+        assert_eq!((player.stack, player.net_result), (0, -1000));
+        assert!(table.seat(*seat).is_none());
+        assert_eq!(table.seats.iter().flatten().map(|seat| seat.stack).sum::<Chips>(), 2000);
+        assert_eq!(table.start_hand().err(), Some(TableError::NotEnoughPlayers));
+    }
+}